@@ -0,0 +1,208 @@
+//! 規則による文節の区切り (自立語とそれに続く付属語をまとめる)
+
+use crate::{escape_csv_field, TokenInfo, TokenizerCore};
+use std::fs::File;
+use std::io::Write;
+
+/// 文節の区切りを決める語の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Content,   // 自立語 (新しい文節を始める)
+    Noun,      // 名詞 (直前の名詞と同じ文節にまとめる)
+    SahenNoun, // サ変接続の名詞 (続く「する」を同じ文節にまとめる)
+    Opening,   // 接頭詞・開き括弧 (続く自立語と同じ文節にまとめる)
+    Attached,  // 付属語・句読点・閉じ括弧 (直前の文節に付ける)
+}
+
+fn role(token: &TokenInfo) -> Role {
+    let detail = token.pos_detail.first().map(|d| d.as_str()).unwrap_or("");
+    match (token.pos.as_str(), detail) {
+        ("助詞" | "助動詞", _) => Role::Attached,
+        ("動詞" | "形容詞", "非自立" | "接尾") => Role::Attached,
+        ("名詞", "接尾") => Role::Attached,
+        ("名詞", "サ変接続") => Role::SahenNoun,
+        ("名詞", _) => Role::Noun,
+        ("接頭詞", _) => Role::Opening,
+        ("記号", "括弧開") => Role::Opening,
+        ("記号", "アルファベット") => Role::Noun,
+        ("記号", _) => Role::Attached,
+        _ => Role::Content,
+    }
+}
+
+/// 辞書の原形が「する」の動詞か
+fn is_suru(token: &TokenInfo) -> bool {
+    token.pos == "動詞" && token.features.get(6).map(|f| f.as_str()) == Some("する")
+}
+
+/// 各トークンに文節の番号を付ける (空白のトークンは None)
+///
+/// 自立語で新しい文節を始め、続く付属語 (助詞・助動詞・非自立の動詞など) と
+/// 句読点をその文節に含める。連続する名詞、サ変接続の名詞と「する」、
+/// 接頭詞・開き括弧と続く自立語は 1 つの文節にまとめる。空白・改行は文節を区切る。
+/// 戻り値は文節の数。
+pub fn assign_bunsetsu(tokens: &mut [TokenInfo]) -> usize {
+    let mut count = 0;
+    let mut last: Option<Role> = None; // 現在の文節の直前の語 (None なら次の語で新しい文節)
+    for token in tokens.iter_mut() {
+        if token.text.trim().is_empty() {
+            token.bunsetsu = None;
+            last = None;
+            continue;
+        }
+        let role = role(token);
+        let continues = match (last, role) {
+            (None, _) => false,
+            (Some(_), Role::Attached) => true,
+            (Some(Role::Opening), _) => true,
+            (Some(Role::Noun | Role::SahenNoun), Role::Noun | Role::SahenNoun) => true,
+            (Some(Role::SahenNoun), Role::Content) => is_suru(token),
+            _ => false,
+        };
+        if !continues {
+            count += 1;
+        }
+        token.bunsetsu = Some(count - 1);
+        // 開き括弧に続く記号 (「『 など) の後も自立語を同じ文節に含める
+        if !(last == Some(Role::Opening) && role == Role::Attached) {
+            last = Some(role);
+        }
+    }
+    count
+}
+
+/// 文節
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bunsetsu {
+    pub id: usize,
+    pub tokens: std::ops::Range<usize>, // 構成するトークンの添字
+    pub text: String,
+    pub head: Option<usize>, // 主辞 (最後の自立語) のトークンの添字
+}
+
+impl Bunsetsu {
+    /// 構成語を "/" で区切った表記
+    pub fn parts_text(&self, tokens: &[TokenInfo]) -> String {
+        tokens[self.tokens.clone()]
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// 文節の番号を付けたトークンから文節の一覧を作る
+pub fn collect_bunsetsu(tokens: &[TokenInfo]) -> Vec<Bunsetsu> {
+    let mut result: Vec<Bunsetsu> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(id) = token.bunsetsu else {
+            continue;
+        };
+        match result.last_mut() {
+            Some(bunsetsu) if bunsetsu.id == id => {
+                bunsetsu.tokens.end = i + 1;
+                bunsetsu.text.push_str(&token.text);
+            }
+            _ => result.push(Bunsetsu {
+                id,
+                tokens: i..i + 1,
+                text: token.text.clone(),
+                head: None,
+            }),
+        }
+        let bunsetsu = result.last_mut().unwrap();
+        if !matches!(role(token), Role::Attached | Role::Opening) {
+            bunsetsu.head = Some(i);
+        }
+    }
+    result
+}
+
+impl TokenizerCore {
+    /// 解析結果の文節の一覧
+    pub fn bunsetsu(&self) -> Vec<Bunsetsu> {
+        collect_bunsetsu(&self.tokens)
+    }
+}
+
+/// 文節の一覧をCSVファイルに保存する (UTF-8 BOM 付き)
+pub fn save_bunsetsu_to_csv(
+    bunsetsu: &[Bunsetsu],
+    tokens: &[TokenInfo],
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    writeln!(file, "文節番号,文節,主辞,主辞の品詞,語数,構成語")?;
+    for b in bunsetsu {
+        let head = b.head.map(|i| &tokens[i]);
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            b.id + 1,
+            escape_csv_field(&b.text),
+            escape_csv_field(head.map(|t| t.text.as_str()).unwrap_or("")),
+            escape_csv_field(&head.map(|t| t.pos_path()).unwrap_or_default()),
+            b.tokens.len(),
+            escape_csv_field(&b.parts_text(tokens))
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens_from_features;
+
+    #[test]
+    fn groups_sahen_noun_with_suru() {
+        // 私は/情報処理を/勉強している。
+        let mut tokens = tokens_from_features(&[
+            ("私", "名詞,代名詞,一般,*,*,*,私,ワタシ,ワタシ"),
+            ("は", "助詞,係助詞,*,*,*,*,は,ハ,ワ"),
+            ("情報", "名詞,一般,*,*,*,*,情報,ジョウホウ,ジョーホー"),
+            ("処理", "名詞,サ変接続,*,*,*,*,処理,ショリ,ショリ"),
+            ("を", "助詞,格助詞,一般,*,*,*,を,ヲ,ヲ"),
+            ("勉強", "名詞,サ変接続,*,*,*,*,勉強,ベンキョウ,ベンキョー"),
+            ("し", "動詞,自立,*,*,サ変・スル,連用形,する,シ,シ"),
+            ("て", "助詞,接続助詞,*,*,*,*,て,テ,テ"),
+            ("いる", "動詞,非自立,*,*,一段,基本形,いる,イル,イル"),
+            ("。", "記号,句点,*,*,*,*,。,。,。"),
+        ]);
+        assert_eq!(assign_bunsetsu(&mut tokens), 3);
+        let ids: Vec<Option<usize>> = tokens.iter().map(|t| t.bunsetsu).collect();
+        let expected = [0, 0, 1, 1, 1, 2, 2, 2, 2, 2];
+        assert_eq!(ids, expected.map(Some).to_vec());
+
+        let bunsetsu = collect_bunsetsu(&tokens);
+        let texts: Vec<&str> = bunsetsu.iter().map(|b| b.text.as_str()).collect();
+        assert_eq!(texts, vec!["私は", "情報処理を", "勉強している。"]);
+        let heads: Vec<Option<usize>> = bunsetsu.iter().map(|b| b.head).collect();
+        assert_eq!(heads, vec![Some(0), Some(3), Some(6)]);
+        assert_eq!(bunsetsu[1].parts_text(&tokens), "情報/処理/を");
+    }
+
+    #[test]
+    fn brackets_join_and_whitespace_separates() {
+        // 「新しい」 本
+        let mut tokens = tokens_from_features(&[
+            ("「", "記号,括弧開,*,*,*,*,「,「,「"),
+            (
+                "新しい",
+                "形容詞,自立,*,*,形容詞・イ段,基本形,新しい,アタラシイ,アタラシイ",
+            ),
+            ("」", "記号,括弧閉,*,*,*,*,」,」,」"),
+            (" ", "記号,空白,*,*,*,*, ,*,*"),
+            ("本", "名詞,一般,*,*,*,*,本,ホン,ホン"),
+        ]);
+        assert_eq!(assign_bunsetsu(&mut tokens), 2);
+        let ids: Vec<Option<usize>> = tokens.iter().map(|t| t.bunsetsu).collect();
+        assert_eq!(ids, vec![Some(0), Some(0), Some(0), None, Some(1)]);
+        let heads: Vec<Option<usize>> = collect_bunsetsu(&tokens).iter().map(|b| b.head).collect();
+        assert_eq!(heads, vec![Some(1), Some(4)]);
+    }
+}
@@ -0,0 +1,253 @@
+//! 共起ネットワークのノード中心性
+
+use crate::{CooccurrenceNetwork, CooccurrenceNode};
+use std::collections::VecDeque;
+
+/// 固有ベクトル中心性のべき乗法の最大反復回数
+const EIGENVECTOR_MAX_ITER: usize = 200;
+/// 固有ベクトル中心性の収束判定の閾値
+const EIGENVECTOR_TOLERANCE: f64 = 1e-9;
+
+/// ノードの中心性指標
+#[derive(Debug, Clone, Default)]
+pub struct NodeCentrality {
+    pub degree: usize,        // 次数
+    pub weighted_degree: f64, // 重み付き次数 (関連度の合計)
+    pub betweenness: f64,     // 媒介中心性 (0..=1 に正規化)
+    pub closeness: f64,       // 近接中心性 (非連結グラフ向けの Wasserman-Faust 補正)
+    pub eigenvector: f64,     // 固有ベクトル中心性 (最大値 1)
+}
+
+/// ノードの大きさ・色の基準として選べる指標
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeMetric {
+    #[default]
+    Frequency,
+    Degree,
+    WeightedDegree,
+    Betweenness,
+    Closeness,
+    Eigenvector,
+}
+
+impl NodeMetric {
+    pub const ALL: [NodeMetric; 6] = [
+        NodeMetric::Frequency,
+        NodeMetric::Degree,
+        NodeMetric::WeightedDegree,
+        NodeMetric::Betweenness,
+        NodeMetric::Closeness,
+        NodeMetric::Eigenvector,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeMetric::Frequency => "出現頻度",
+            NodeMetric::Degree => "次数",
+            NodeMetric::WeightedDegree => "重み付き次数",
+            NodeMetric::Betweenness => "媒介中心性",
+            NodeMetric::Closeness => "近接中心性",
+            NodeMetric::Eigenvector => "固有ベクトル中心性",
+        }
+    }
+}
+
+impl CooccurrenceNode {
+    /// 指定した指標の値
+    pub fn metric(&self, metric: NodeMetric) -> f64 {
+        match metric {
+            NodeMetric::Frequency => self.frequency as f64,
+            NodeMetric::Degree => self.centrality.degree as f64,
+            NodeMetric::WeightedDegree => self.centrality.weighted_degree,
+            NodeMetric::Betweenness => self.centrality.betweenness,
+            NodeMetric::Closeness => self.centrality.closeness,
+            NodeMetric::Eigenvector => self.centrality.eigenvector,
+        }
+    }
+}
+
+impl CooccurrenceNetwork {
+    /// 各ノードの中心性を計算して `centrality` に設定する
+    ///
+    /// 媒介中心性・近接中心性はエッジの重みを考慮しない最短経路 (ホップ数) で計算する。
+    /// 重み付き次数・固有ベクトル中心性には関連度が 0 より大きいエッジの関連度を用いる。
+    pub fn compute_centrality(&mut self) {
+        let n = self.nodes.len();
+        let mut neighbors: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+        for edge in &self.edges {
+            if edge.source == edge.target {
+                continue;
+            }
+            let w = edge.weight.max(0.0);
+            neighbors[edge.source].push((edge.target, w));
+            neighbors[edge.target].push((edge.source, w));
+        }
+
+        let betweenness = betweenness(&neighbors);
+        let closeness = closeness(&neighbors);
+        let eigenvector = eigenvector(&neighbors);
+
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            node.centrality = NodeCentrality {
+                degree: neighbors[i].len(),
+                weighted_degree: neighbors[i].iter().fold(0.0, |acc, (_, w)| acc + w),
+                betweenness: betweenness[i],
+                closeness: closeness[i],
+                eigenvector: eigenvector[i],
+            };
+        }
+    }
+}
+
+/// 1 つの始点からの最短経路の情報
+struct ShortestPaths {
+    distance: Vec<Option<usize>>,  // 距離 (到達不能なら None)
+    paths: Vec<f64>,               // 最短経路の数
+    order: Vec<usize>,             // 訪問順
+    predecessors: Vec<Vec<usize>>, // 最短経路上の直前のノード
+}
+
+/// 始点から各ノードへの BFS を行う
+fn bfs(neighbors: &[Vec<(usize, f64)>], start: usize) -> ShortestPaths {
+    let n = neighbors.len();
+    let mut distance: Vec<Option<usize>> = vec![None; n];
+    let mut paths = vec![0.0; n];
+    let mut order = Vec::with_capacity(n);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut queue = VecDeque::new();
+
+    distance[start] = Some(0);
+    paths[start] = 1.0;
+    queue.push_back(start);
+    while let Some(v) = queue.pop_front() {
+        order.push(v);
+        let dv = distance[v].unwrap_or(0);
+        for &(u, _) in &neighbors[v] {
+            if distance[u].is_none() {
+                distance[u] = Some(dv + 1);
+                queue.push_back(u);
+            }
+            if distance[u] == Some(dv + 1) {
+                paths[u] += paths[v];
+                predecessors[u].push(v);
+            }
+        }
+    }
+    ShortestPaths {
+        distance,
+        paths,
+        order,
+        predecessors,
+    }
+}
+
+/// 媒介中心性 (Brandes 2001)
+fn betweenness(neighbors: &[Vec<(usize, f64)>]) -> Vec<f64> {
+    let n = neighbors.len();
+    let mut centrality = vec![0.0; n];
+    for s in 0..n {
+        let sp = bfs(neighbors, s);
+        let mut dependency = vec![0.0; n];
+        for &w in sp.order.iter().rev() {
+            for &v in &sp.predecessors[w] {
+                dependency[v] += sp.paths[v] / sp.paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    // 無向グラフなので各経路を 2 回数えている。ノード対の数で正規化する
+    if n > 2 {
+        let scale = 1.0 / ((n - 1) * (n - 2)) as f64;
+        for c in centrality.iter_mut() {
+            *c *= scale;
+        }
+    }
+    centrality
+}
+
+/// 近接中心性 (到達可能なノードのみで計算し、到達可能な割合で補正する)
+fn closeness(neighbors: &[Vec<(usize, f64)>]) -> Vec<f64> {
+    let n = neighbors.len();
+    (0..n)
+        .map(|s| {
+            let reached: Vec<usize> = bfs(neighbors, s).distance.into_iter().flatten().collect();
+            let total: usize = reached.iter().sum();
+            let r = reached.len() - 1;
+            if total == 0 || n < 2 {
+                0.0
+            } else {
+                (r as f64 / total as f64) * (r as f64 / (n - 1) as f64)
+            }
+        })
+        .collect()
+}
+
+/// 固有ベクトル中心性 (べき乗法, 最大値が 1 になるよう正規化)
+fn eigenvector(neighbors: &[Vec<(usize, f64)>]) -> Vec<f64> {
+    let n = neighbors.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut x = vec![1.0; n];
+    for _ in 0..EIGENVECTOR_MAX_ITER {
+        // A + I を掛けて二部グラフでの振動を防ぐ
+        let mut next = x.clone();
+        for (v, list) in neighbors.iter().enumerate() {
+            for &(u, w) in list {
+                next[v] += w * x[u];
+            }
+        }
+        let max = next.iter().cloned().fold(0.0, f64::max);
+        if max <= 0.0 {
+            return vec![0.0; n];
+        }
+        for value in next.iter_mut() {
+            *value /= max;
+        }
+        let diff: f64 = next.iter().zip(&x).map(|(a, b)| (a - b).abs()).sum();
+        x = next;
+        if diff < EIGENVECTOR_TOLERANCE * n as f64 {
+            break;
+        }
+    }
+    // 孤立ノードは自己ループ分の値だけが残るため 0 とする
+    for (value, list) in x.iter_mut().zip(neighbors) {
+        if list.is_empty() {
+            *value = 0.0;
+        }
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{network_from_edges, CooccurrenceNode};
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn centrality_on_a_path_graph() {
+        // 0 - 1 - 2 - 3 のパスグラフ
+        let mut network = network_from_edges(4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+        network.compute_centrality();
+        let values =
+            |f: fn(&CooccurrenceNode) -> f64| -> Vec<f64> { network.nodes.iter().map(f).collect() };
+
+        let degree: Vec<usize> = network.nodes.iter().map(|n| n.centrality.degree).collect();
+        assert_eq!(degree, vec![1, 2, 2, 1]);
+        // 内側のノードはそれぞれ 3 組中 2 組のノード対の最短経路上にある
+        assert_close(
+            &values(|n| n.centrality.betweenness),
+            &[0.0, 2.0 / 3.0, 2.0 / 3.0, 0.0],
+        );
+        // 端のノードは距離の合計 1 + 2 + 3、内側のノードは 1 + 1 + 2
+        assert_close(&values(|n| n.centrality.closeness), &[0.5, 0.75, 0.75, 0.5]);
+    }
+}
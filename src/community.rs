@@ -0,0 +1,422 @@
+//! 共起ネットワークのコミュニティ検出 (サブグラフ検出)
+
+use crate::CooccurrenceNetwork;
+use std::collections::HashMap;
+
+/// Walktrap でランダムウォークを行うステップ数 (Pons & Latapy の推奨値)
+const WALKTRAP_STEPS: usize = 4;
+
+/// コミュニティ検出の手法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommunityMethod {
+    #[default]
+    Louvain, // モジュラリティ最大化 (Louvain 法)
+    Walktrap,            // ランダムウォーク (Walktrap 法)
+    ConnectedComponents, // 連結成分
+}
+
+impl CommunityMethod {
+    pub const ALL: [CommunityMethod; 3] = [
+        CommunityMethod::Louvain,
+        CommunityMethod::Walktrap,
+        CommunityMethod::ConnectedComponents,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CommunityMethod::Louvain => "モジュラリティ (Louvain)",
+            CommunityMethod::Walktrap => "ランダムウォーク (Walktrap)",
+            CommunityMethod::ConnectedComponents => "連結成分",
+        }
+    }
+}
+
+/// 重み付き隣接リスト (自己ループを含む, 無向グラフは両方向に格納)
+type Adjacency = Vec<Vec<(usize, f64)>>;
+
+impl CooccurrenceNetwork {
+    /// コミュニティを検出して各ノードの `community` とネットワークの `modularity` を設定する
+    ///
+    /// コミュニティ番号は所属ノード数の多い順に 0 から振り直す。
+    /// 関連度が 0 以下のエッジはコミュニティ検出では無視する。
+    pub fn detect_communities(&mut self, method: CommunityMethod) {
+        let n = self.nodes.len();
+        let mut adjacency: Adjacency = vec![Vec::new(); n];
+        for edge in &self.edges {
+            if edge.weight > 0.0 && edge.source != edge.target {
+                adjacency[edge.source].push((edge.target, edge.weight));
+                adjacency[edge.target].push((edge.source, edge.weight));
+            }
+        }
+
+        let membership = match method {
+            CommunityMethod::Louvain => louvain(&adjacency),
+            CommunityMethod::Walktrap => walktrap(&adjacency, WALKTRAP_STEPS),
+            CommunityMethod::ConnectedComponents => connected_components(&adjacency),
+        };
+        let membership = renumber_by_size(&membership);
+
+        self.modularity = modularity(&adjacency, &membership);
+        for (node, community) in self.nodes.iter_mut().zip(membership) {
+            node.community = community;
+        }
+    }
+
+    /// コミュニティ番号ごとの所属ノード数 (番号順)
+    pub fn community_sizes(&self) -> Vec<usize> {
+        let count = self
+            .nodes
+            .iter()
+            .map(|n| n.community + 1)
+            .max()
+            .unwrap_or(0);
+        let mut sizes = vec![0; count];
+        for node in &self.nodes {
+            sizes[node.community] += 1;
+        }
+        sizes
+    }
+}
+
+/// 所属ノード数の降順 (同数は最小ノード番号順) にコミュニティ番号を振り直す
+fn renumber_by_size(membership: &[usize]) -> Vec<usize> {
+    let mut groups: HashMap<usize, (usize, usize)> = HashMap::new(); // 旧番号 → (サイズ, 最小ノード)
+    for (node, &c) in membership.iter().enumerate() {
+        let entry = groups.entry(c).or_insert((0, node));
+        entry.0 += 1;
+    }
+    let mut order: Vec<(usize, usize, usize)> = groups
+        .into_iter()
+        .map(|(c, (size, first))| (c, size, first))
+        .collect();
+    order.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.2.cmp(&b.2)));
+    let new_id: HashMap<usize, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, (c, _, _))| (*c, i))
+        .collect();
+    membership.iter().map(|c| new_id[c]).collect()
+}
+
+/// 分割のモジュラリティ Q を計算する
+fn modularity(adjacency: &Adjacency, membership: &[usize]) -> f64 {
+    let total: f64 = adjacency.iter().flatten().map(|(_, w)| w).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let count = membership.iter().max().map_or(0, |m| m + 1);
+    let mut inner = vec![0.0; count];
+    let mut degree = vec![0.0; count];
+    for (i, neighbors) in adjacency.iter().enumerate() {
+        for &(j, w) in neighbors {
+            degree[membership[i]] += w;
+            if membership[i] == membership[j] {
+                inner[membership[i]] += w;
+            }
+        }
+    }
+    inner
+        .iter()
+        .zip(&degree)
+        .map(|(e, a)| e / total - (a / total).powi(2))
+        .sum()
+}
+
+/// 連結成分ごとにコミュニティとする
+fn connected_components(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut membership = vec![usize::MAX; n];
+    let mut next = 0;
+    for start in 0..n {
+        if membership[start] != usize::MAX {
+            continue;
+        }
+        let mut stack = vec![start];
+        membership[start] = next;
+        while let Some(v) = stack.pop() {
+            for &(u, _) in &adjacency[v] {
+                if membership[u] == usize::MAX {
+                    membership[u] = next;
+                    stack.push(u);
+                }
+            }
+        }
+        next += 1;
+    }
+    membership
+}
+
+/// Louvain 法 (Blondel et al. 2008) によるモジュラリティ最大化
+///
+/// ノードの処理順は番号順で固定しているため、同じネットワークからは常に同じ分割が得られる。
+fn louvain(adjacency: &Adjacency) -> Vec<usize> {
+    let mut membership: Vec<usize> = (0..adjacency.len()).collect();
+    let mut graph = adjacency.clone();
+
+    loop {
+        let (partition, improved) = louvain_one_level(&graph);
+        if !improved {
+            break;
+        }
+        for c in membership.iter_mut() {
+            *c = partition[*c];
+        }
+        graph = aggregate(&graph, &partition);
+    }
+    membership
+}
+
+/// Louvain 法の 1 段階: ノードを隣接コミュニティへ移動して局所的にモジュラリティを上げる
+///
+/// 戻り値のコミュニティ番号は 0 から連番に振り直される。
+fn louvain_one_level(graph: &Adjacency) -> (Vec<usize>, bool) {
+    let n = graph.len();
+    let degree: Vec<f64> = graph
+        .iter()
+        .map(|neighbors| neighbors.iter().map(|(_, w)| w).sum())
+        .collect();
+    let total: f64 = degree.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    if total <= 0.0 {
+        return (community, false);
+    }
+
+    let mut community_degree = degree.clone();
+    let mut improved = false;
+    loop {
+        let mut moved = false;
+        for i in 0..n {
+            let current = community[i];
+            let mut links: HashMap<usize, f64> = HashMap::new();
+            for &(j, w) in &graph[i] {
+                if j != i {
+                    *links.entry(community[j]).or_insert(0.0) += w;
+                }
+            }
+
+            community_degree[current] -= degree[i];
+            let gain = |c: usize, link: f64| link - community_degree[c] * degree[i] / total;
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            let mut candidates: Vec<(usize, f64)> = links.into_iter().collect();
+            candidates.sort_by_key(|(c, _)| *c);
+            for (c, link) in candidates {
+                let g = gain(c, link);
+                if g > best_gain + 1e-12 {
+                    best = c;
+                    best_gain = g;
+                }
+            }
+            community_degree[best] += degree[i];
+
+            if best != current {
+                community[i] = best;
+                moved = true;
+                improved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    (compact(&community), improved)
+}
+
+/// コミュニティを 1 ノードに縮約したグラフを作る (内部エッジは自己ループになる)
+fn aggregate(graph: &Adjacency, partition: &[usize]) -> Adjacency {
+    let count = partition.iter().max().map_or(0, |m| m + 1);
+    let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+    for (i, neighbors) in graph.iter().enumerate() {
+        for &(j, w) in neighbors {
+            *weights[partition[i]].entry(partition[j]).or_insert(0.0) += w;
+        }
+    }
+    weights
+        .into_iter()
+        .map(|m| {
+            let mut neighbors: Vec<(usize, f64)> = m.into_iter().collect();
+            neighbors.sort_by_key(|(j, _)| *j);
+            neighbors
+        })
+        .collect()
+}
+
+/// Walktrap 法 (Pons & Latapy 2005) による階層的クラスタリング
+///
+/// 長さ `steps` のランダムウォークの到達確率が近いコミュニティ同士を Ward 法で併合し、
+/// 併合の過程でモジュラリティが最大となった分割を返す。
+fn walktrap(adjacency: &Adjacency, steps: usize) -> Vec<usize> {
+    let n = adjacency.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // 各ノードに自己ループを加えた遷移確率 (自己ループの重みは平均エッジ重み)
+    let weights: Vec<f64> = adjacency.iter().flatten().map(|(_, w)| *w).collect();
+    let loop_weight = if weights.is_empty() {
+        1.0
+    } else {
+        weights.iter().sum::<f64>() / weights.len() as f64
+    };
+    let mut graph = adjacency.clone();
+    for (i, neighbors) in graph.iter_mut().enumerate() {
+        neighbors.push((i, loop_weight));
+    }
+    let degree: Vec<f64> = graph
+        .iter()
+        .map(|neighbors| neighbors.iter().map(|(_, w)| w).sum())
+        .collect();
+
+    // 各ノードから steps 歩後の到達確率ベクトル
+    let probability: Vec<Vec<f64>> = (0..n)
+        .map(|start| {
+            let mut p = vec![0.0; n];
+            p[start] = 1.0;
+            for _ in 0..steps {
+                let mut next = vec![0.0; n];
+                for (k, &pk) in p.iter().enumerate() {
+                    if pk == 0.0 {
+                        continue;
+                    }
+                    for &(j, w) in &graph[k] {
+                        next[j] += pk * w / degree[k];
+                    }
+                }
+                p = next;
+            }
+            p
+        })
+        .collect();
+
+    struct Community {
+        size: usize,
+        probability: Vec<f64>,
+    }
+    let mut communities: Vec<Option<Community>> = probability
+        .into_iter()
+        .map(|p| {
+            Some(Community {
+                size: 1,
+                probability: p,
+            })
+        })
+        .collect();
+
+    let delta_sigma = |a: &Community, b: &Community| -> f64 {
+        let distance: f64 = a
+            .probability
+            .iter()
+            .zip(&b.probability)
+            .zip(&degree)
+            .map(|((x, y), d)| (x - y).powi(2) / d)
+            .sum();
+        (a.size * b.size) as f64 / (a.size + b.size) as f64 * distance / n as f64
+    };
+
+    // 隣接するコミュニティ対とその併合コスト
+    let mut pairs: HashMap<(usize, usize), f64> = HashMap::new();
+    for (i, neighbors) in adjacency.iter().enumerate() {
+        for &(j, _) in neighbors {
+            if i < j && !pairs.contains_key(&(i, j)) {
+                let cost = delta_sigma(
+                    communities[i].as_ref().unwrap(),
+                    communities[j].as_ref().unwrap(),
+                );
+                pairs.insert((i, j), cost);
+            }
+        }
+    }
+
+    let mut membership: Vec<usize> = (0..n).collect();
+    let mut best_membership = membership.clone();
+    let mut best_modularity = modularity(adjacency, &membership);
+
+    while let Some((&(a, b), _)) = pairs
+        .iter()
+        .min_by(|x, y| x.1.total_cmp(y.1).then_with(|| x.0.cmp(y.0)))
+    {
+        // a と b を併合して新しいコミュニティ (番号は communities の末尾) を作る
+        let ca = communities[a].take().unwrap();
+        let cb = communities[b].take().unwrap();
+        let size = ca.size + cb.size;
+        let merged = Community {
+            size,
+            probability: ca
+                .probability
+                .iter()
+                .zip(&cb.probability)
+                .map(|(x, y)| (x * ca.size as f64 + y * cb.size as f64) / size as f64)
+                .collect(),
+        };
+        let id = communities.len();
+
+        let mut neighbors: Vec<usize> = pairs
+            .keys()
+            .filter(|(x, y)| *x == a || *x == b || *y == a || *y == b)
+            .map(|&(x, y)| if x == a || x == b { y } else { x })
+            .filter(|&c| c != a && c != b)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        pairs.retain(|(x, y), _| *x != a && *x != b && *y != a && *y != b);
+        for c in neighbors {
+            let cost = delta_sigma(&merged, communities[c].as_ref().unwrap());
+            pairs.insert((c, id), cost);
+        }
+        communities.push(Some(merged));
+
+        for m in membership.iter_mut() {
+            if *m == a || *m == b {
+                *m = id;
+            }
+        }
+        let q = modularity(adjacency, &compact(&membership));
+        if q > best_modularity + 1e-12 {
+            best_modularity = q;
+            best_membership = membership.clone();
+        }
+    }
+
+    compact(&best_membership)
+}
+
+/// コミュニティ番号を 0 から連番に振り直す
+fn compact(membership: &[usize]) -> Vec<usize> {
+    let mut renumber: HashMap<usize, usize> = HashMap::new();
+    membership
+        .iter()
+        .map(|c| {
+            let next = renumber.len();
+            *renumber.entry(*c).or_insert(next)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_from_edges;
+
+    #[test]
+    fn louvain_splits_two_cliques_joined_by_a_bridge() {
+        let mut edges = Vec::new();
+        for offset in [0, 4] {
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    edges.push((offset + i, offset + j, 1.0));
+                }
+            }
+        }
+        edges.push((3, 4, 1.0));
+        let mut network = network_from_edges(8, &edges);
+        network.detect_communities(CommunityMethod::Louvain);
+
+        let membership: Vec<usize> = network.nodes.iter().map(|n| n.community).collect();
+        assert_eq!(membership, vec![0, 0, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(network.community_sizes(), vec![4, 4]);
+        // エッジ 13 本のうち各コミュニティの内部に 6 本、次数の合計は 13 ずつ:
+        // Q = 2 * (6/13 - (13/26)^2) = 12/13 - 1/2
+        assert!((network.modularity - (12.0 / 13.0 - 0.5)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,221 @@
+//! 連続する名詞を 1 語の複合名詞にまとめる後処理
+
+use crate::TokenInfo;
+
+/// 複合名詞にまとめる規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompoundConfig {
+    pub enabled: bool,  // 連続する名詞をまとめる
+    pub prefixes: bool, // 名詞の前の接頭詞 (例: 「新」「各」) を含める
+    pub suffixes: bool, // 名詞の後の接尾 (例: 「者」「化」「年」) を含める
+    pub numbers: bool,  // 数 (と数に付く接頭詞「第」「約」など) を含める
+}
+
+impl Default for CompoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefixes: true,
+            suffixes: true,
+            numbers: true,
+        }
+    }
+}
+
+/// 複合名詞の中での語の役割
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Prefix, // 接頭詞 (後に名詞が続く時だけまとめる)
+    Head,   // 名詞
+    Suffix, // 接尾 (前に名詞がある時だけまとめる)
+    Other,  // まとめない語
+}
+
+impl CompoundConfig {
+    fn role(&self, token: &TokenInfo) -> Role {
+        let detail = token.pos_detail.first().map(|d| d.as_str()).unwrap_or("");
+        match (token.pos.as_str(), detail) {
+            ("接頭詞", "名詞接続") if self.prefixes => Role::Prefix,
+            ("接頭詞", "数接続") if self.prefixes && self.numbers => Role::Prefix,
+            ("名詞", "接尾") if self.suffixes => Role::Suffix,
+            ("名詞", "数") if self.numbers => Role::Head,
+            ("名詞", "接尾" | "数" | "非自立" | "代名詞" | "特殊") => Role::Other,
+            ("名詞", _) => Role::Head,
+            _ => Role::Other,
+        }
+    }
+}
+
+/// 複数の語を 1 語にまとめる
+fn join(parts: &[TokenInfo]) -> TokenInfo {
+    let first = &parts[0];
+    let last = &parts[parts.len() - 1];
+    let text: String = parts.iter().map(|t| t.text.as_str()).collect();
+    let reading: String = if parts.iter().all(|t| !t.reading.is_empty()) {
+        parts.iter().map(|t| t.reading.as_str()).collect()
+    } else {
+        String::new()
+    };
+    // 末尾がサ変接続の名詞なら「〜する」が続けられるので細分類を引き継ぐ
+    let detail = if last.pos == "名詞"
+        && last.pos_detail.first().map(|d| d.as_str()) == Some("サ変接続")
+    {
+        "サ変接続"
+    } else {
+        "一般"
+    };
+    let reading_feature = if reading.is_empty() {
+        "*"
+    } else {
+        reading.as_str()
+    };
+    let features = [
+        "名詞",
+        detail,
+        "*",
+        "*",
+        "*",
+        "*",
+        &text,
+        reading_feature,
+        reading_feature,
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect();
+    TokenInfo {
+        pos: "名詞".to_string(),
+        pos_detail: vec![detail.to_string()],
+        reading,
+        features,
+        unknown: parts.iter().any(|t| t.unknown),
+        bunsetsu: None,
+        start: first.start,
+        end: last.end,
+        text,
+    }
+}
+
+/// 間を空けずに隣り合う名詞 (規則に応じて接頭詞・接尾・数を含む) を 1 語にまとめる
+pub fn join_compounds(tokens: Vec<TokenInfo>, config: &CompoundConfig) -> Vec<TokenInfo> {
+    if !config.enabled {
+        return tokens;
+    }
+    let mut joined = Vec::with_capacity(tokens.len());
+    let mut group: Vec<TokenInfo> = Vec::new();
+    let mut has_head = false;
+    let flush = |group: &mut Vec<TokenInfo>, has_head: &mut bool, joined: &mut Vec<TokenInfo>| {
+        if *has_head && group.len() >= 2 {
+            joined.push(join(group));
+            group.clear();
+        } else {
+            joined.append(group);
+        }
+        *has_head = false;
+    };
+
+    for token in tokens {
+        let role = config.role(&token);
+        let adjacent = group.last().map(|t| t.end == token.start).unwrap_or(false);
+        let accepted = adjacent
+            && match role {
+                Role::Prefix => !has_head,
+                Role::Head => true,
+                Role::Suffix => has_head,
+                Role::Other => false,
+            };
+        if !accepted {
+            flush(&mut group, &mut has_head, &mut joined);
+            if !matches!(role, Role::Prefix | Role::Head) {
+                joined.push(token);
+                continue;
+            }
+        }
+        has_head |= role == Role::Head;
+        group.push(token);
+    }
+    flush(&mut group, &mut has_head, &mut joined);
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens_from_features;
+
+    const JOUHOU: (&str, &str) = ("情報", "名詞,一般,*,*,*,*,情報,ジョウホウ,ジョーホー");
+    const SHORI: (&str, &str) = ("処理", "名詞,サ変接続,*,*,*,*,処理,ショリ,ショリ");
+    const GIJUTSU: (&str, &str) = ("技術", "名詞,一般,*,*,*,*,技術,ギジュツ,ギジュツ");
+    const SHA: (&str, &str) = ("者", "名詞,接尾,一般,*,*,*,者,シャ,シャ");
+
+    fn config() -> CompoundConfig {
+        CompoundConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn texts(tokens: &[TokenInfo]) -> Vec<&str> {
+        tokens.iter().map(|t| t.text.as_str()).collect()
+    }
+
+    #[test]
+    fn joins_nouns_and_suffix() {
+        let tokens = tokens_from_features(&[JOUHOU, SHORI, GIJUTSU, SHA]);
+        let joined = join_compounds(tokens.clone(), &config());
+        assert_eq!(texts(&joined), vec!["情報処理技術者"]);
+        let word = &joined[0];
+        assert_eq!(word.pos_path(), "名詞-一般");
+        assert_eq!(word.reading, "ジョウホウショリギジュツシャ");
+        assert_eq!((word.start, word.end), (0, "情報処理技術者".len()));
+
+        let no_suffix = CompoundConfig {
+            suffixes: false,
+            ..config()
+        };
+        let joined = join_compounds(tokens.clone(), &no_suffix);
+        assert_eq!(texts(&joined), vec!["情報処理技術", "者"]);
+
+        let disabled = CompoundConfig::default();
+        assert_eq!(
+            texts(&join_compounds(tokens, &disabled)),
+            vec!["情報", "処理", "技術", "者"]
+        );
+    }
+
+    #[test]
+    fn keeps_sahen_detail_of_last_noun() {
+        let joined = join_compounds(tokens_from_features(&[JOUHOU, SHORI]), &config());
+        assert_eq!(texts(&joined), vec!["情報処理"]);
+        assert_eq!(joined[0].pos_path(), "名詞-サ変接続");
+    }
+
+    #[test]
+    fn joins_prefix_only_before_noun() {
+        let shin = ("新", "接頭詞,名詞接続,*,*,*,*,新,シン,シン");
+        let wo = ("を", "助詞,格助詞,一般,*,*,*,を,ヲ,ヲ");
+        let joined = join_compounds(tokens_from_features(&[shin, JOUHOU]), &config());
+        assert_eq!(texts(&joined), vec!["新情報"]);
+        let joined = join_compounds(tokens_from_features(&[shin, wo, JOUHOU]), &config());
+        assert_eq!(texts(&joined), vec!["新", "を", "情報"]);
+    }
+
+    #[test]
+    fn joins_numbers_with_number_prefix() {
+        let words = [
+            ("第", "接頭詞,数接続,*,*,*,*,第,ダイ,ダイ"),
+            ("三", "名詞,数,*,*,*,*,三,サン,サン"),
+            ("章", "名詞,接尾,助数詞,*,*,*,章,ショウ,ショー"),
+        ];
+        let joined = join_compounds(tokens_from_features(&words), &config());
+        assert_eq!(texts(&joined), vec!["第三章"]);
+        assert_eq!(joined[0].reading, "ダイサンショウ");
+
+        let no_numbers = CompoundConfig {
+            numbers: false,
+            ..config()
+        };
+        let joined = join_compounds(tokens_from_features(&words), &no_numbers);
+        assert_eq!(texts(&joined), vec!["第", "三", "章"]);
+    }
+}
@@ -0,0 +1,220 @@
+//! 異なり語ごとの頻度表
+
+use crate::{PosFilter, TokenizerCore};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 頻度表の 1 行 (表層形と品詞の組ごと)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequencyEntry {
+    pub word: String,
+    pub pos: String,     // 品詞 (細分類まで "-" で連結)
+    pub reading: String, // 最初に出現したトークンの読み
+    pub frequency: usize,
+}
+
+/// 頻度表の並べ替え基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrequencySortKey {
+    #[default]
+    Frequency,
+    Word,
+    Pos,
+    Reading,
+}
+
+impl FrequencySortKey {
+    pub const ALL: [FrequencySortKey; 4] = [
+        FrequencySortKey::Frequency,
+        FrequencySortKey::Word,
+        FrequencySortKey::Pos,
+        FrequencySortKey::Reading,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FrequencySortKey::Frequency => "頻度",
+            FrequencySortKey::Word => "単語",
+            FrequencySortKey::Pos => "品詞",
+            FrequencySortKey::Reading => "読み",
+        }
+    }
+
+    /// 行の添字を並べ替える (同じ値の行は頻度の降順、単語の順)
+    pub fn sort(&self, entries: &[FrequencyEntry], order: &mut [usize], descending: bool) {
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&entries[a], &entries[b]);
+            let ordering = match self {
+                FrequencySortKey::Frequency => a.frequency.cmp(&b.frequency),
+                FrequencySortKey::Word => a.word.cmp(&b.word),
+                FrequencySortKey::Pos => a.pos.cmp(&b.pos),
+                FrequencySortKey::Reading => a.reading.cmp(&b.reading),
+            };
+            let ordering = if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            ordering
+                .then_with(|| b.frequency.cmp(&a.frequency))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+    }
+}
+
+/// 頻度表の絞り込み条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequencyFilter {
+    pub pos_filter: Option<PosFilter>, // None なら品詞で絞り込まない
+    pub pattern: String,               // 単語に含まれる文字列 (空ならすべて)
+    pub use_regex: bool,               // `pattern` を正規表現として扱う
+    pub min_frequency: usize,
+}
+
+impl Default for FrequencyFilter {
+    fn default() -> Self {
+        Self {
+            pos_filter: None,
+            pattern: String::new(),
+            use_regex: false,
+            min_frequency: 1,
+        }
+    }
+}
+
+impl FrequencyFilter {
+    /// 条件に合う行の添字を返す (不正な正規表現ならエラー)
+    pub fn apply(&self, entries: &[FrequencyEntry]) -> Result<Vec<usize>, regex::Error> {
+        let regex = if self.use_regex && !self.pattern.is_empty() {
+            Some(Regex::new(&self.pattern)?)
+        } else {
+            None
+        };
+        let matches_word = |word: &str| match &regex {
+            Some(regex) => regex.is_match(word),
+            None => word.contains(self.pattern.as_str()),
+        };
+        Ok(entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.frequency >= self.min_frequency)
+            .filter(|(_, e)| {
+                self.pos_filter
+                    .as_ref()
+                    .map(|filter| filter.matches(&e.pos))
+                    .unwrap_or(true)
+            })
+            .filter(|(_, e)| matches_word(&e.word))
+            .map(|(i, _)| i)
+            .collect())
+    }
+}
+
+impl TokenizerCore {
+    /// 解析結果の頻度表 (ストップワードと空白を除き、頻度の降順)
+    pub fn frequency_entries(&self) -> Vec<FrequencyEntry> {
+        let mut index: HashMap<(&str, String), usize> = HashMap::new();
+        let mut entries: Vec<FrequencyEntry> = Vec::new();
+        for token in &self.tokens {
+            if token.text.trim().is_empty() || self.is_stopword(&token.text) {
+                continue;
+            }
+            let pos = token.pos_path();
+            match index.get(&(token.text.as_str(), pos.clone())) {
+                Some(&i) => entries[i].frequency += 1,
+                None => {
+                    index.insert((token.text.as_str(), pos.clone()), entries.len());
+                    entries.push(FrequencyEntry {
+                        word: token.text.clone(),
+                        pos,
+                        reading: token.reading.clone(),
+                        frequency: 1,
+                    });
+                }
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.frequency
+                .cmp(&a.frequency)
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<FrequencyEntry> {
+        [
+            ("大学", "名詞-一般", "ダイガク", 5),
+            ("東京", "名詞-固有名詞-地域-一般", "トウキョウ", 3),
+            ("行く", "動詞-自立", "イク", 3),
+            ("東", "名詞-一般", "ヒガシ", 1),
+        ]
+        .into_iter()
+        .map(|(word, pos, reading, frequency)| FrequencyEntry {
+            word: word.to_string(),
+            pos: pos.to_string(),
+            reading: reading.to_string(),
+            frequency,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn filter_combines_frequency_pos_and_pattern() {
+        let entries = entries();
+        let filter = |f: FrequencyFilter| f.apply(&entries).unwrap();
+        assert_eq!(filter(FrequencyFilter::default()), [0, 1, 2, 3]);
+        assert_eq!(
+            filter(FrequencyFilter {
+                min_frequency: 3,
+                ..Default::default()
+            }),
+            [0, 1, 2]
+        );
+        assert_eq!(
+            filter(FrequencyFilter {
+                pos_filter: Some(PosFilter::including(&["名詞"])),
+                pattern: "東".to_string(),
+                ..Default::default()
+            }),
+            [1, 3]
+        );
+        assert_eq!(
+            filter(FrequencyFilter {
+                pattern: "^東.".to_string(),
+                use_regex: true,
+                ..Default::default()
+            }),
+            [1]
+        );
+
+        let invalid = FrequencyFilter {
+            pattern: "東(".to_string(),
+            use_regex: true,
+            ..Default::default()
+        };
+        assert!(invalid.apply(&entries).is_err());
+        // 正規表現でなければそのままの文字列で探す
+        let literal = FrequencyFilter {
+            use_regex: false,
+            ..invalid
+        };
+        assert_eq!(literal.apply(&entries).unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn ties_are_sorted_by_frequency_then_word() {
+        let entries = entries();
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        FrequencySortKey::Word.sort(&entries, &mut order, false);
+        assert_eq!(order, [0, 3, 1, 2]);
+        FrequencySortKey::Frequency.sort(&entries, &mut order, false);
+        assert_eq!(order, [3, 1, 2, 0]);
+        FrequencySortKey::Pos.sort(&entries, &mut order, true);
+        assert_eq!(order, [1, 0, 3, 2]);
+    }
+}
@@ -0,0 +1,365 @@
+//! 参照コーパスとの比較による特徴語 (キーネス) 分析
+
+use crate::{escape_csv_field, split_csv_record, TokenizerCore};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+/// 出現頻度が 0 の場合に %DIFF の分母に用いる値 (Gabrielatos & Marchi 2012)
+const PERCENT_DIFF_ZERO: f64 = 1e-18;
+/// 出現頻度が 0 の場合に Log Ratio で用いる補正頻度 (Hardie 2014)
+const LOG_RATIO_ZERO: f64 = 0.5;
+
+/// 特徴語の順位付けに用いる指標
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeynessMeasure {
+    LogLikelihood,
+    ChiSquare,
+    PercentDiff,
+    LogRatio,
+}
+
+impl KeynessMeasure {
+    pub const ALL: [KeynessMeasure; 4] = [
+        KeynessMeasure::LogLikelihood,
+        KeynessMeasure::ChiSquare,
+        KeynessMeasure::PercentDiff,
+        KeynessMeasure::LogRatio,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeynessMeasure::LogLikelihood => "対数尤度比 (LL)",
+            KeynessMeasure::ChiSquare => "カイ二乗値",
+            KeynessMeasure::PercentDiff => "%DIFF",
+            KeynessMeasure::LogRatio => "Log Ratio",
+        }
+    }
+}
+
+/// キーネス分析の条件
+#[derive(Debug, Clone)]
+pub struct KeynessConfig {
+    pub measure: KeynessMeasure, // 並べ替えに使う指標
+    pub min_freq: usize,         // 対象・参照いずれかでの最小出現頻度
+    pub min_log_likelihood: f64, // 有意性の閾値 (3.84 = p < 0.05, 6.63 = p < 0.01)
+}
+
+impl Default for KeynessConfig {
+    fn default() -> Self {
+        Self {
+            measure: KeynessMeasure::LogLikelihood,
+            min_freq: 3,
+            min_log_likelihood: 3.84,
+        }
+    }
+}
+
+/// 単語ごとのキーネス指標
+#[derive(Debug, Clone)]
+pub struct KeynessEntry {
+    pub word: String,
+    pub target_freq: usize,    // 対象テキストでの頻度
+    pub reference_freq: usize, // 参照コーパスでの頻度
+    pub log_likelihood: f64,
+    pub chi_square: f64,
+    pub percent_diff: f64,
+    pub log_ratio: f64,
+}
+
+impl KeynessEntry {
+    /// 指定した指標の値
+    pub fn score(&self, measure: KeynessMeasure) -> f64 {
+        match measure {
+            KeynessMeasure::LogLikelihood => self.log_likelihood,
+            KeynessMeasure::ChiSquare => self.chi_square,
+            KeynessMeasure::PercentDiff => self.percent_diff,
+            KeynessMeasure::LogRatio => self.log_ratio,
+        }
+    }
+}
+
+/// キーネス分析の結果
+#[derive(Debug, Clone, Default)]
+pub struct KeynessResult {
+    pub positive: Vec<KeynessEntry>, // 対象テキストで多い語 (正の特徴語)
+    pub negative: Vec<KeynessEntry>, // 対象テキストで少ない語 (負の特徴語)
+    pub target_total: usize,         // 対象テキストの総語数
+    pub reference_total: usize,      // 参照コーパスの総語数
+}
+
+/// 2×2 分割表から各指標を計算する
+///
+/// a: 対象での頻度, b: 参照での頻度, c: 対象の総語数, d: 参照の総語数
+fn keyness_scores(a: f64, b: f64, c: f64, d: f64) -> (f64, f64, f64, f64) {
+    let n = c + d;
+    let e1 = c * (a + b) / n;
+    let e2 = d * (a + b) / n;
+    let term = |o: f64, e: f64| if o > 0.0 { o * (o / e).ln() } else { 0.0 };
+    let log_likelihood = 2.0 * (term(a, e1) + term(b, e2));
+
+    let denom = (a + b) * (n - a - b) * c * d;
+    let chi_square = if denom > 0.0 {
+        n * (a * (d - b) - b * (c - a)).powi(2) / denom
+    } else {
+        0.0
+    };
+
+    let target_rel = a / c;
+    let reference_rel = b / d;
+    let percent_diff = (target_rel - reference_rel) / reference_rel.max(PERCENT_DIFF_ZERO) * 100.0;
+    let log_ratio = ((a.max(LOG_RATIO_ZERO) / c) / (b.max(LOG_RATIO_ZERO) / d)).log2();
+
+    (log_likelihood, chi_square, percent_diff, log_ratio)
+}
+
+/// 頻度リストを計算する (空白のみのトークンは除く)
+fn count_words<'a>(words: impl Iterator<Item = &'a str>) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for word in words {
+        if !word.trim().is_empty() {
+            *frequencies.entry(word.to_string()).or_insert(0) += 1;
+        }
+    }
+    frequencies
+}
+
+impl TokenizerCore {
+    /// 任意のテキストの単語頻度を計算する (参照コーパスの作成用)
+    pub fn count_frequencies(&self, text: &str) -> HashMap<String, usize> {
+        let infos = self.tokenize_to_infos(text);
+        count_words(
+            infos
+                .iter()
+                .map(|t| t.text.as_str())
+                .filter(|w| !self.is_stopword(w)),
+        )
+    }
+
+    /// 解析済みテキスト (`word_frequencies`) と参照頻度リストを比較して特徴語を求める
+    ///
+    /// 対数尤度比が閾値未満の語は除外し、正・負それぞれを指定指標の絶対値の降順で返す。
+    pub fn compute_keyness(
+        &self,
+        reference: &HashMap<String, usize>,
+        config: &KeynessConfig,
+    ) -> KeynessResult {
+        let target: HashMap<&str, usize> = self
+            .word_frequencies
+            .iter()
+            .filter(|(w, _)| !w.trim().is_empty())
+            .map(|(w, c)| (w.as_str(), *c))
+            .collect();
+        let target_total: usize = target.values().sum();
+        let reference_total: usize = reference
+            .iter()
+            .filter(|(w, _)| !w.trim().is_empty() && !self.is_stopword(w))
+            .map(|(_, c)| *c)
+            .sum();
+
+        let mut result = KeynessResult {
+            target_total,
+            reference_total,
+            ..Default::default()
+        };
+        if target_total == 0 || reference_total == 0 {
+            return result;
+        }
+
+        let mut words: Vec<&str> = target.keys().copied().collect();
+        words.extend(
+            reference
+                .keys()
+                .map(|w| w.as_str())
+                .filter(|w| {
+                    !w.trim().is_empty() && !target.contains_key(w) && !self.is_stopword(w)
+                }),
+        );
+
+        let (c, d) = (target_total as f64, reference_total as f64);
+        for word in words {
+            let target_freq = target.get(word).copied().unwrap_or(0);
+            let reference_freq = reference.get(word).copied().unwrap_or(0);
+            if target_freq.max(reference_freq) < config.min_freq {
+                continue;
+            }
+            let (a, b) = (target_freq as f64, reference_freq as f64);
+            let (log_likelihood, chi_square, percent_diff, log_ratio) = keyness_scores(a, b, c, d);
+            if log_likelihood < config.min_log_likelihood {
+                continue;
+            }
+            let entry = KeynessEntry {
+                word: word.to_string(),
+                target_freq,
+                reference_freq,
+                log_likelihood,
+                chi_square,
+                percent_diff,
+                log_ratio,
+            };
+            if a / c >= b / d {
+                result.positive.push(entry);
+            } else {
+                result.negative.push(entry);
+            }
+        }
+
+        let measure = config.measure;
+        for list in [&mut result.positive, &mut result.negative] {
+            list.sort_by(|x, y| {
+                y.score(measure)
+                    .abs()
+                    .total_cmp(&x.score(measure).abs())
+                    .then_with(|| x.word.cmp(&y.word))
+            });
+        }
+        result
+    }
+
+    /// 解析済みテキストの頻度リストを CSV ファイルに保存 (参照コーパスとして再利用可能)
+    pub fn save_frequency_list(&self, path: std::path::PathBuf) -> Result<(), std::io::Error> {
+        let mut entries: Vec<(&String, &usize)> = self
+            .word_frequencies
+            .iter()
+            .filter(|(w, _)| !w.trim().is_empty())
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut file = File::create(path)?;
+
+        // UTF-8 BOMを書き込み（Excel対応）
+        file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+        writeln!(file, "単語,出現頻度")?;
+        for (word, count) in entries {
+            writeln!(file, "{},{}", escape_csv_field(word), count)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 頻度リストファイルを読み込む
+///
+/// 1 行に「単語,頻度」(タブ区切りも可) を記述する。先頭列を単語、末尾列を頻度として扱い、
+/// 頻度が数値でない行 (ヘッダーなど) は読み飛ばす。同じ単語が複数行にある場合は合算する。
+/// 引用符で囲んだフィールドに対応し、`save_frequency_list` で保存したファイルをそのまま読める。
+pub fn load_frequency_list(
+    path: std::path::PathBuf,
+) -> Result<HashMap<String, usize>, std::io::Error> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut frequencies = HashMap::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_start_matches('\u{feff}').trim_end();
+        let delimiter = if line.contains('\t') { '\t' } else { ',' };
+        let fields = split_csv_record(line, delimiter);
+        if fields.len() < 2 {
+            continue;
+        }
+        let word = fields[0].trim();
+        let Ok(count) = fields[fields.len() - 1].trim().parse::<usize>() else {
+            continue;
+        };
+        if word.is_empty() {
+            continue;
+        }
+        *frequencies.entry(word.to_string()).or_insert(0) += count;
+    }
+    Ok(frequencies)
+}
+
+/// キーネス分析の結果を CSV ファイルに保存 (UTF-8 BOM 付き)
+pub fn save_keyness_to_csv(
+    result: &KeynessResult,
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    writeln!(
+        file,
+        "区分,単語,対象頻度,参照頻度,対数尤度比,カイ二乗値,%DIFF,Log Ratio"
+    )?;
+    let rows = result
+        .positive
+        .iter()
+        .map(|e| ("正", e))
+        .chain(result.negative.iter().map(|e| ("負", e)));
+    for (kind, entry) in rows {
+        writeln!(
+            file,
+            "{},{},{},{},{:.3},{:.3},{:.3},{:.3}",
+            kind,
+            escape_csv_field(&entry.word),
+            entry.target_freq,
+            entry.reference_freq,
+            entry.log_likelihood,
+            entry.chi_square,
+            entry.percent_diff,
+            entry.log_ratio
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_frequency_list_reads_quoted_fields() {
+        let path = std::env::temp_dir().join("jp_word_segment_frequency_list_test.csv");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "\u{feff}単語,出現頻度").unwrap();
+        for (word, count) in [(",", 3), ("\"引用\"", 2), ("東京", 1), ("東京", 4)] {
+            writeln!(file, "{},{}", escape_csv_field(word), count).unwrap();
+        }
+        drop(file);
+
+        let frequencies = load_frequency_list(path.clone()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(frequencies.len(), 3);
+        assert_eq!(frequencies[","], 3);
+        assert_eq!(frequencies["\"引用\""], 2);
+        assert_eq!(frequencies["東京"], 5);
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn scores_match_a_hand_computed_table() {
+        // 対象 100 語中 20 回、参照 200 語中 10 回: 期待度数は 10 と 20
+        let (ll, chi, diff, ratio) = keyness_scores(20.0, 10.0, 100.0, 200.0);
+        assert_close(ll, 2.0 * (20.0 * 2f64.ln() + 10.0 * 0.5f64.ln()));
+        // 100/10 + 100/90 + 100/20 + 100/180
+        assert_close(chi, 50.0 / 3.0);
+        assert_close(diff, 300.0);
+        assert_close(ratio, 2.0);
+    }
+
+    #[test]
+    fn zero_frequency_cell_is_corrected() {
+        // 参照に現れない語: 期待度数はどちらも 2.5
+        let (ll, chi, diff, ratio) = keyness_scores(5.0, 0.0, 100.0, 100.0);
+        assert_close(ll, 10.0 * 2f64.ln());
+        assert_close(chi, 200.0 / 39.0);
+        assert_close(diff, 0.05 / PERCENT_DIFF_ZERO * 100.0);
+        assert_close(ratio, 10f64.log2());
+
+        // 対象に現れない語
+        let (ll, chi, diff, ratio) = keyness_scores(0.0, 5.0, 100.0, 100.0);
+        assert_close(ll, 10.0 * 2f64.ln());
+        assert_close(chi, 200.0 / 39.0);
+        assert_close(diff, -100.0);
+        assert_close(ratio, -(10f64.log2()));
+    }
+}
@@ -0,0 +1,1029 @@
+//! 共起ネットワークのレイアウト (Barnes-Hut 近似による力指向配置と静的な配置方法)
+
+use crate::CooccurrenceNetwork;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Arc};
+
+/// ワールド座標 (原点がキャンバス中央、y 軸は下向き)
+pub type Point = (f32, f32);
+
+/// 4 分木の最大の深さ (同じ座標のノードが重なった場合の打ち切り)
+const QUADTREE_MAX_DEPTH: usize = 24;
+/// 位置が重なったとみなす距離
+const MIN_DISTANCE: f32 = 0.01;
+/// 収束とみなすまでに平均移動量が閾値を下回り続けるステップ数
+const SETTLE_STEPS: usize = 30;
+
+/// 力指向レイアウトの設定
+#[derive(Debug, Clone)]
+pub struct ForceLayoutConfig {
+    pub theta: f32,            // Barnes-Hut の近似パラメータ (0 で厳密計算)
+    pub time_step: f32,        // 1 ステップの時間幅
+    pub damping: f32,          // 速度の減衰率
+    pub max_speed: f32,        // 速度の上限 (温度 1 のとき)
+    pub cooling: f32,          // 1 ステップごとに温度に掛ける係数
+    pub gravity: f32,          // 中心への引力の強さ
+    pub spacing: f32,          // 最適距離の係数 (面積 / ノード数 の平方根に掛ける)
+    pub settle_threshold: f32, // 収束とみなす 1 ステップあたりの平均移動量
+}
+
+impl Default for ForceLayoutConfig {
+    fn default() -> Self {
+        Self {
+            theta: 0.8,
+            time_step: 0.6,
+            damping: 0.82,
+            max_speed: 60.0,
+            cooling: 0.99,
+            gravity: 0.01,
+            spacing: 0.35,
+            settle_threshold: 0.05,
+        }
+    }
+}
+
+/// 力指向レイアウトの状態
+///
+/// 反発力は Barnes-Hut 法 (4 分木による O(n log n) 近似)、引力はエッジに沿ったばねとして計算する。
+/// 速度の上限は温度とともに下がり (焼きなまし)、振動したまま止まらない状態を防ぐ。
+#[derive(Debug, Clone)]
+pub struct ForceLayout {
+    pub config: ForceLayoutConfig,
+    pub positions: Vec<Point>,
+    pub pinned: Vec<bool>, // 固定したノード (レイアウトで動かさない)
+    velocities: Vec<Point>,
+    temperature: f32,     // 速度の上限に掛ける係数 (1 から下がっていく)
+    movement: f32,        // 直近のステップの平均移動量
+    settled_steps: usize, // 平均移動量が閾値を下回り続けているステップ数
+}
+
+impl Default for ForceLayout {
+    fn default() -> Self {
+        Self::new(Vec::new(), ForceLayoutConfig::default())
+    }
+}
+
+impl ForceLayout {
+    /// 指定した初期配置から始める
+    pub fn new(positions: Vec<Point>, config: ForceLayoutConfig) -> Self {
+        let n = positions.len();
+        Self {
+            config,
+            positions,
+            pinned: vec![false; n],
+            velocities: vec![(0.0, 0.0); n],
+            temperature: 1.0,
+            movement: 0.0,
+            settled_steps: 0,
+        }
+    }
+
+    /// 半径 `radius` の円周上に等間隔に並べた初期配置
+    pub fn circle(n: usize, radius: f32) -> Vec<Point> {
+        (0..n)
+            .map(|i| {
+                let theta = i as f32 / n as f32 * std::f32::consts::TAU;
+                (radius * theta.cos(), radius * theta.sin())
+            })
+            .collect()
+    }
+
+    /// 配置が落ち着いたか
+    pub fn is_converged(&self) -> bool {
+        self.settled_steps >= SETTLE_STEPS
+    }
+
+    /// 直近のステップの平均移動量
+    pub fn movement(&self) -> f32 {
+        self.movement
+    }
+
+    /// 速度・温度と収束判定をリセットする (ノードを動かしたあとの再開用)
+    pub fn reset_motion(&mut self) {
+        self.velocities = vec![(0.0, 0.0); self.positions.len()];
+        self.temperature = 1.0;
+        self.movement = 0.0;
+        self.settled_steps = 0;
+    }
+
+    /// 指定ノードの速度を 0 にする (ドラッグ中など)
+    pub fn stop_node(&mut self, index: usize) {
+        if let Some(v) = self.velocities.get_mut(index) {
+            *v = (0.0, 0.0);
+        }
+    }
+
+    /// 1 ステップ進める
+    ///
+    /// `area` は描画領域の大きさで、ノード間の最適距離の計算に用いる。
+    /// `held` のノードと固定したノードは動かさない。収束したら `true` を返す。
+    pub fn step(
+        &mut self,
+        network: &CooccurrenceNetwork,
+        area: (f32, f32),
+        held: Option<usize>,
+    ) -> bool {
+        let n = self.positions.len().min(network.nodes.len());
+        if n < 2 {
+            self.settled_steps = SETTLE_STEPS;
+            return true;
+        }
+        if self.velocities.len() != self.positions.len() {
+            self.velocities = vec![(0.0, 0.0); self.positions.len()];
+        }
+        let config = &self.config;
+
+        // 最適距離 k
+        let k = (area.0 * area.1 / n as f32).sqrt() * config.spacing;
+        let k2 = k * k;
+
+        // 反発力 (Barnes-Hut)
+        let tree = QuadTree::build(&self.positions[..n]);
+        let mut forces: Vec<Point> = (0..n)
+            .map(|i| tree.repulsion(i, self.positions[i], k2, config.theta))
+            .collect();
+
+        // 引力 (エッジに沿って)
+        let weight_range = network.weight_range();
+        for edge in &network.edges {
+            if edge.source >= n || edge.target >= n {
+                continue;
+            }
+            let (sx, sy) = self.positions[edge.source];
+            let (tx, ty) = self.positions[edge.target];
+            let (dx, dy) = (tx - sx, ty - sy);
+            let dist = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+            let weight = 0.5 + 0.5 * network.relative_weight(edge.weight, weight_range);
+            let f = (dist * dist) / k * weight;
+            let (fx, fy) = (dx / dist * f, dy / dist * f);
+            forces[edge.source].0 += fx;
+            forces[edge.source].1 += fy;
+            forces[edge.target].0 -= fx;
+            forces[edge.target].1 -= fy;
+        }
+
+        // 中心への弱い引力、速度・位置更新 (減衰付き)
+        let dt = config.time_step;
+        let max_speed = config.max_speed * self.temperature;
+        let mut total_movement = 0.0;
+        for (i, force) in forces.iter().enumerate() {
+            if Some(i) == held || self.pinned.get(i).copied().unwrap_or(false) {
+                self.velocities[i] = (0.0, 0.0);
+                continue;
+            }
+            let (px, py) = self.positions[i];
+            let (fx, fy) = (force.0 - px * config.gravity, force.1 - py * config.gravity);
+            let (vx, vy) = self.velocities[i];
+            let (mut vx, mut vy) = (
+                (vx + fx * dt) * config.damping,
+                (vy + fy * dt) * config.damping,
+            );
+            let speed = (vx * vx + vy * vy).sqrt();
+            if speed > max_speed {
+                vx *= max_speed / speed;
+                vy *= max_speed / speed;
+            }
+            self.velocities[i] = (vx, vy);
+            self.positions[i] = (px + vx * dt, py + vy * dt);
+            total_movement += (vx * vx + vy * vy).sqrt() * dt;
+        }
+
+        self.temperature *= config.cooling;
+        self.movement = total_movement / n as f32;
+        if self.movement < config.settle_threshold {
+            self.settled_steps += 1;
+        } else {
+            self.settled_steps = 0;
+        }
+        self.is_converged()
+    }
+}
+
+/// Barnes-Hut 法の 4 分木のセル
+#[derive(Debug, Clone)]
+struct QuadCell {
+    center: Point,        // セルの中心
+    half: f32,            // セルの幅の半分
+    mass: f32,            // 含まれるノード数
+    mass_center: Point,   // 含まれるノードの重心
+    children: [usize; 4], // 子セルの添字 (0 は子なし。根は子になりえない)
+    bodies: Vec<usize>,   // 葉に含まれるノード
+}
+
+impl QuadCell {
+    fn new(center: Point, half: f32) -> Self {
+        Self {
+            center,
+            half,
+            mass: 0.0,
+            mass_center: (0.0, 0.0),
+            children: [0; 4],
+            bodies: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children == [0; 4]
+    }
+}
+
+/// ノード位置の 4 分木
+struct QuadTree<'a> {
+    cells: Vec<QuadCell>,
+    positions: &'a [Point],
+}
+
+impl<'a> QuadTree<'a> {
+    fn build(positions: &'a [Point]) -> Self {
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        for &(x, y) in positions {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let half = ((max_x - min_x).max(max_y - min_y) / 2.0).max(1.0) * 1.01;
+        let center = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+        let mut tree = Self {
+            cells: vec![QuadCell::new(center, half)],
+            positions,
+        };
+        for i in 0..positions.len() {
+            tree.insert(i);
+        }
+        // 重心を確定させる (挿入時は位置の総和を保持している)
+        for cell in tree.cells.iter_mut() {
+            if cell.mass > 0.0 {
+                cell.mass_center.0 /= cell.mass;
+                cell.mass_center.1 /= cell.mass;
+            }
+        }
+        tree
+    }
+
+    fn insert(&mut self, body: usize) {
+        let (x, y) = self.positions[body];
+        let mut cell = 0;
+        let mut depth = 0;
+        loop {
+            self.cells[cell].mass += 1.0;
+            self.cells[cell].mass_center.0 += x;
+            self.cells[cell].mass_center.1 += y;
+
+            if self.cells[cell].is_leaf() {
+                if self.cells[cell].bodies.is_empty() || depth >= QUADTREE_MAX_DEPTH {
+                    self.cells[cell].bodies.push(body);
+                    return;
+                }
+                // 既存のノードを子セルに移して分割する
+                let existing = std::mem::take(&mut self.cells[cell].bodies);
+                for other in existing {
+                    let child = self.child_for(cell, self.positions[other]);
+                    let (ox, oy) = self.positions[other];
+                    let c = &mut self.cells[child];
+                    c.mass += 1.0;
+                    c.mass_center.0 += ox;
+                    c.mass_center.1 += oy;
+                    c.bodies.push(other);
+                }
+            }
+            cell = self.child_for(cell, (x, y));
+            depth += 1;
+        }
+    }
+
+    /// 座標を含む子セルを返す (なければ作る)
+    fn child_for(&mut self, cell: usize, (x, y): Point) -> usize {
+        let QuadCell { center, half, .. } = self.cells[cell];
+        let quadrant = usize::from(x >= center.0) | (usize::from(y >= center.1) << 1);
+        if self.cells[cell].children[quadrant] == 0 {
+            let h = half / 2.0;
+            let child_center = (
+                center.0 + if x >= center.0 { h } else { -h },
+                center.1 + if y >= center.1 { h } else { -h },
+            );
+            self.cells.push(QuadCell::new(child_center, h));
+            let index = self.cells.len() - 1;
+            self.cells[cell].children[quadrant] = index;
+        }
+        self.cells[cell].children[quadrant]
+    }
+
+    /// ノード `body` (位置 `p`) が受ける反発力 (大きさ k² / 距離)
+    fn repulsion(&self, body: usize, p: Point, k2: f32, theta: f32) -> Point {
+        let mut force = (0.0, 0.0);
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.mass == 0.0 {
+                continue;
+            }
+            if cell.is_leaf() {
+                for &other in &cell.bodies {
+                    if other != body {
+                        add_repulsion(&mut force, body, p, self.positions[other], 1.0, k2);
+                    }
+                }
+                continue;
+            }
+            let (dx, dy) = (p.0 - cell.mass_center.0, p.1 - cell.mass_center.1);
+            let dist = (dx * dx + dy * dy).sqrt();
+            let contains = (p.0 - cell.center.0).abs() <= cell.half
+                && (p.1 - cell.center.1).abs() <= cell.half;
+            if !contains && cell.half * 2.0 < theta * dist {
+                // 十分遠いセルは重心に集まった 1 つの質点とみなす
+                add_repulsion(&mut force, body, p, cell.mass_center, cell.mass, k2);
+            } else {
+                stack.extend(cell.children.iter().copied().filter(|&c| c != 0));
+            }
+        }
+        force
+    }
+}
+
+/// `from` から `p` の向きに質量 `mass` 分の反発力を加える
+fn add_repulsion(force: &mut Point, body: usize, p: Point, from: Point, mass: f32, k2: f32) {
+    let (mut dx, mut dy) = (p.0 - from.0, p.1 - from.1);
+    let mut dist = (dx * dx + dy * dy).sqrt();
+    if dist < MIN_DISTANCE {
+        // 重なったノードはノードごとに決まった向きへ押し出す
+        dx = (body as f32 * 0.3).cos();
+        dy = (body as f32 * 0.7).sin();
+        dist = MIN_DISTANCE;
+    }
+    let f = mass * k2 / dist;
+    let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    force.0 += dx / len * f;
+    force.1 += dy / len * f;
+}
+
+/// 配置方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutMethod {
+    #[default]
+    Force, // 力指向 (画面上で逐次計算)
+    KamadaKawai,
+    FruchtermanReingold,
+    CircularByCommunity, // コミュニティごとの円形配置
+    Mds,                 // 古典的多次元尺度構成法
+}
+
+impl LayoutMethod {
+    pub const ALL: [LayoutMethod; 5] = [
+        LayoutMethod::Force,
+        LayoutMethod::KamadaKawai,
+        LayoutMethod::FruchtermanReingold,
+        LayoutMethod::CircularByCommunity,
+        LayoutMethod::Mds,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LayoutMethod::Force => "力指向 (リアルタイム)",
+            LayoutMethod::KamadaKawai => "Kamada-Kawai",
+            LayoutMethod::FruchtermanReingold => "Fruchterman-Reingold",
+            LayoutMethod::CircularByCommunity => "コミュニティ別円形",
+            LayoutMethod::Mds => "古典的 MDS",
+        }
+    }
+
+    /// 乱数の種を使う配置方法か
+    pub fn uses_seed(&self) -> bool {
+        matches!(self, LayoutMethod::FruchtermanReingold)
+    }
+}
+
+/// Fruchterman-Reingold 法の反復回数
+const FR_ITERATIONS: usize = 300;
+/// Fruchterman-Reingold 法の中心への引力の強さ
+const FR_GRAVITY: f64 = 2.0;
+/// Kamada-Kawai 法で 1 ノードあたりに許す更新回数
+const KK_MAX_UPDATES_PER_NODE: usize = 50;
+/// Kamada-Kawai 法の収束判定の閾値 (エネルギー勾配の大きさ)
+const KK_TOLERANCE: f64 = 1e-3;
+/// MDS の固有ベクトル計算 (べき乗法) の最大反復回数
+const MDS_ITERATIONS: usize = 1000;
+/// MDS で正の固有値の軸を探す最大の軸数
+const MDS_MAX_AXES: usize = 6;
+/// 重なり解消で保つ最小間隔 (配置の広がりに対する比)
+const OVERLAP_MIN_DISTANCE: f32 = 0.06;
+/// 重なり解消の最大反復回数
+const OVERLAP_ITERATIONS: usize = 50;
+
+/// 反復計算の進捗の通知先
+///
+/// 進捗 (0.0〜1.0) を受け取り、false を返したら計算を打ち切る (途中までの配置を返す)。
+type Progress<'a> = &'a dyn Fn(f32) -> bool;
+
+/// 指定した方法でノードの配置を計算する
+///
+/// 結果は原点を中心に、最も遠いノードまでの距離が `radius` になるよう拡大縮小する。
+/// 同じネットワーク・方法・`seed` からは常に同じ配置が得られる。
+/// `Force` は力指向レイアウトの初期配置 (円周上) を返す。
+pub fn compute_layout(
+    network: &CooccurrenceNetwork,
+    method: LayoutMethod,
+    seed: u64,
+    radius: f32,
+) -> Vec<Point> {
+    layout_with_progress(network, method, seed, radius, &|_| true)
+}
+
+fn layout_with_progress(
+    network: &CooccurrenceNetwork,
+    method: LayoutMethod,
+    seed: u64,
+    radius: f32,
+    progress: Progress,
+) -> Vec<Point> {
+    let n = network.nodes.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let positions = match method {
+        LayoutMethod::Force => return ForceLayout::circle(n, radius),
+        LayoutMethod::KamadaKawai => kamada_kawai(network, progress),
+        LayoutMethod::FruchtermanReingold => fruchterman_reingold(network, seed, progress),
+        LayoutMethod::CircularByCommunity => circular_by_community(network),
+        LayoutMethod::Mds => classical_mds(network, progress),
+    };
+    fit_to_radius(positions, radius)
+}
+
+/// 別スレッドで計算中の配置 (破棄すると計算を打ち切る)
+///
+/// Kamada-Kawai・MDS などはノード数の 2 乗以上の計算量になるため、画面の更新を止めないよう
+/// 別スレッドで計算する。
+pub struct LayoutTask {
+    receiver: mpsc::Receiver<Vec<Point>>,
+    progress: Arc<AtomicU32>, // 進捗 (0.0〜1.0 の f32 のビット列)
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LayoutTask {
+    /// 配置の計算を始める (引数は `compute_layout` と同じ)
+    pub fn spawn(
+        network: CooccurrenceNetwork,
+        method: LayoutMethod,
+        seed: u64,
+        radius: f32,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (task_progress, task_cancelled) = (progress.clone(), cancelled.clone());
+        std::thread::spawn(move || {
+            let report = |value: f32| {
+                task_progress.store(value.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+                !task_cancelled.load(Ordering::Relaxed)
+            };
+            let positions = layout_with_progress(&network, method, seed, radius, &report);
+            // 受け取る側が破棄されていれば結果は捨てる
+            let _ = sender.send(positions);
+        });
+        Self {
+            receiver,
+            progress,
+            cancelled,
+        }
+    }
+
+    /// 進捗 (0.0〜1.0)
+    pub fn progress(&self) -> f32 {
+        f32::from_bits(self.progress.load(Ordering::Relaxed))
+    }
+
+    /// 計算が終わっていれば配置を返す (計算中なら None)
+    pub fn try_take(&self) -> Result<Option<Vec<Point>>, std::io::Error> {
+        match self.receiver.try_recv() {
+            Ok(positions) => Ok(Some(positions)),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err(std::io::Error::other("配置の計算が異常終了しました"))
+            }
+        }
+    }
+}
+
+impl Drop for LayoutTask {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 隣接リスト (自己ループは除く)
+fn adjacency(network: &CooccurrenceNetwork) -> Vec<Vec<usize>> {
+    let mut neighbors = vec![Vec::new(); network.nodes.len()];
+    for edge in &network.edges {
+        if edge.source != edge.target {
+            neighbors[edge.source].push(edge.target);
+            neighbors[edge.target].push(edge.source);
+        }
+    }
+    neighbors
+}
+
+/// 全ノード対のホップ数による距離
+///
+/// 到達できないノード対には (最大距離 + 1) を割り当てて、連結成分どうしを離して配置する。
+fn graph_distances(network: &CooccurrenceNetwork) -> Vec<Vec<f64>> {
+    let neighbors = adjacency(network);
+    let n = neighbors.len();
+    let mut distances = vec![vec![f64::INFINITY; n]; n];
+    let mut max_distance: f64 = 1.0;
+    for (s, row) in distances.iter_mut().enumerate() {
+        row[s] = 0.0;
+        let mut queue = std::collections::VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            for &u in &neighbors[v] {
+                if row[u].is_infinite() {
+                    row[u] = row[v] + 1.0;
+                    max_distance = max_distance.max(row[u]);
+                    queue.push_back(u);
+                }
+            }
+        }
+    }
+    for row in distances.iter_mut() {
+        for d in row.iter_mut() {
+            if d.is_infinite() {
+                *d = max_distance + 1.0;
+            }
+        }
+    }
+    distances
+}
+
+/// Kamada-Kawai 法 (1989)
+///
+/// グラフ上の距離に比例した長さのばねで全ノード対をつなぎ、エネルギー勾配が最大のノードから
+/// Newton-Raphson 法で 1 つずつ動かす。初期配置は円周上。
+fn kamada_kawai(network: &CooccurrenceNetwork, progress: Progress) -> Vec<Point> {
+    let n = network.nodes.len();
+    let distances = graph_distances(network);
+    let mut pos: Vec<(f64, f64)> = ForceLayout::circle(n, 1.0)
+        .into_iter()
+        .map(|(x, y)| (x as f64, y as f64))
+        .collect();
+    if n < 2 {
+        return to_points(&pos);
+    }
+
+    // ばねの自然長 l_ij = d_ij、強さ k_ij = 1 / d_ij² (全体の大きさは最後に正規化する)。
+    // ノード m の位置 pm がノード i (位置 pi) から受ける勾配の成分
+    let term = |m: usize, pm: (f64, f64), i: usize, pi: (f64, f64)| -> (f64, f64) {
+        let (dx, dy) = (pm.0 - pi.0, pm.1 - pi.1);
+        let dist = (dx * dx + dy * dy).sqrt().max(1e-9);
+        let (l, k) = (distances[m][i], 1.0 / (distances[m][i] * distances[m][i]));
+        (k * (dx - l * dx / dist), k * (dy - l * dy / dist))
+    };
+    let gradient = |pos: &[(f64, f64)], m: usize| -> (f64, f64) {
+        (0..n).filter(|&i| i != m).fold((0.0, 0.0), |acc, i| {
+            let t = term(m, pos[m], i, pos[i]);
+            (acc.0 + t.0, acc.1 + t.1)
+        })
+    };
+
+    // 円周上の初期配置を平均距離に合わせて拡大する
+    let mean_distance = distances.iter().flatten().sum::<f64>() / (n * (n - 1)) as f64;
+    for p in pos.iter_mut() {
+        p.0 *= mean_distance * n as f64 / std::f64::consts::TAU;
+        p.1 *= mean_distance * n as f64 / std::f64::consts::TAU;
+    }
+
+    let mut gradients: Vec<(f64, f64)> = (0..n).map(|m| gradient(&pos, m)).collect();
+    for update in 0..KK_MAX_UPDATES_PER_NODE * n {
+        if update % n == 0 && !progress(update as f32 / (KK_MAX_UPDATES_PER_NODE * n) as f32) {
+            break;
+        }
+        let norm = |g: (f64, f64)| (g.0 * g.0 + g.1 * g.1).sqrt();
+        let Some((m, delta)) = gradients
+            .iter()
+            .map(|&g| norm(g))
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            break;
+        };
+        if delta < KK_TOLERANCE {
+            break;
+        }
+
+        // ヘッセ行列を求めて 2 × 2 の連立方程式を解く
+        let (mut hxx, mut hxy, mut hyy) = (0.0, 0.0, 0.0);
+        for i in 0..n {
+            if i == m {
+                continue;
+            }
+            let (dx, dy) = (pos[m].0 - pos[i].0, pos[m].1 - pos[i].1);
+            let dist = (dx * dx + dy * dy).sqrt().max(1e-9);
+            let dist3 = dist * dist * dist;
+            let (l, k) = (distances[m][i], 1.0 / (distances[m][i] * distances[m][i]));
+            hxx += k * (1.0 - l * dy * dy / dist3);
+            hxy += k * l * dx * dy / dist3;
+            hyy += k * (1.0 - l * dx * dx / dist3);
+        }
+        let det = hxx * hyy - hxy * hxy;
+        if det.abs() < 1e-12 {
+            break;
+        }
+        let (gx, gy) = gradients[m];
+        let old = pos[m];
+        pos[m].0 += (-gx * hyy + gy * hxy) / det;
+        pos[m].1 += (gx * hxy - gy * hxx) / det;
+
+        // 動かしたノードの勾配を計算し直し、他のノードの勾配は m の寄与だけを差し替える
+        for i in 0..n {
+            if i == m {
+                continue;
+            }
+            let before = term(i, pos[i], m, old);
+            let after = term(i, pos[i], m, pos[m]);
+            gradients[i].0 += after.0 - before.0;
+            gradients[i].1 += after.1 - before.1;
+        }
+        gradients[m] = gradient(&pos, m);
+    }
+    to_points(&pos)
+}
+
+/// Fruchterman-Reingold 法 (1991)
+///
+/// `seed` から決まる擬似乱数で初期配置を作り、温度を線形に下げながら一定回数反復する。
+fn fruchterman_reingold(
+    network: &CooccurrenceNetwork,
+    seed: u64,
+    progress: Progress,
+) -> Vec<Point> {
+    let n = network.nodes.len();
+    let mut rng = SplitMix64(seed);
+    let mut pos: Vec<(f64, f64)> = (0..n)
+        .map(|_| (rng.next_f64() * 2.0 - 1.0, rng.next_f64() * 2.0 - 1.0))
+        .collect();
+    let neighbors = adjacency(network);
+    let k = (4.0 / n as f64).sqrt(); // 面積 2 × 2 に対する最適距離
+    let initial_temperature = 0.1;
+
+    for iteration in 0..FR_ITERATIONS {
+        if !progress(iteration as f32 / FR_ITERATIONS as f32) {
+            break;
+        }
+        let temperature = initial_temperature * (1.0 - iteration as f64 / FR_ITERATIONS as f64);
+        let mut displacement = vec![(0.0, 0.0); n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (mut dx, mut dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+                let mut dist = (dx * dx + dy * dy).sqrt();
+                if dist < 1e-9 {
+                    dx = rng.next_f64() - 0.5;
+                    dy = rng.next_f64() - 0.5;
+                    dist = (dx * dx + dy * dy).sqrt().max(1e-9);
+                }
+                let f = k * k / dist;
+                displacement[i].0 += dx / dist * f;
+                displacement[i].1 += dy / dist * f;
+                displacement[j].0 -= dx / dist * f;
+                displacement[j].1 -= dy / dist * f;
+            }
+        }
+        // 中心への引力 (連結成分どうしが際限なく離れていくのを防ぐ)
+        for (d, p) in displacement.iter_mut().zip(&pos) {
+            d.0 -= p.0 * FR_GRAVITY;
+            d.1 -= p.1 * FR_GRAVITY;
+        }
+        for (v, list) in neighbors.iter().enumerate() {
+            for &u in list {
+                // 隣接リストは両方向に持つので、各エッジで v 側だけ動かす
+                let (dx, dy) = (pos[v].0 - pos[u].0, pos[v].1 - pos[u].1);
+                let dist = (dx * dx + dy * dy).sqrt().max(1e-9);
+                let f = dist * dist / k;
+                displacement[v].0 -= dx / dist * f;
+                displacement[v].1 -= dy / dist * f;
+            }
+        }
+        for (p, d) in pos.iter_mut().zip(&displacement) {
+            let length = (d.0 * d.0 + d.1 * d.1).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature);
+                p.0 += d.0 / length * step;
+                p.1 += d.1 / length * step;
+            }
+        }
+    }
+    to_points(&pos)
+}
+
+/// コミュニティごとの円形配置
+///
+/// コミュニティを大きい順に大きな円周上へ並べ、各コミュニティのノードを出現頻度の降順に
+/// 小さな円周上へ並べる。小円の半径はコミュニティの大きさの平方根に比例する。
+fn circular_by_community(network: &CooccurrenceNetwork) -> Vec<Point> {
+    let sizes = network.community_sizes();
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); sizes.len()];
+    for (i, node) in network.nodes.iter().enumerate() {
+        members[node.community].push(i);
+    }
+    for list in members.iter_mut() {
+        list.sort_by(|&a, &b| {
+            network.nodes[b]
+                .frequency
+                .cmp(&network.nodes[a].frequency)
+                .then_with(|| network.nodes[a].word.cmp(&network.nodes[b].word))
+        });
+    }
+    let groups: Vec<&Vec<usize>> = members.iter().filter(|m| !m.is_empty()).collect();
+
+    let mut positions = vec![(0.0, 0.0); network.nodes.len()];
+    if groups.len() == 1 {
+        for (rank, &i) in groups[0].iter().enumerate() {
+            let theta = rank as f64 / groups[0].len() as f64 * std::f64::consts::TAU;
+            positions[i] = (theta.cos(), theta.sin());
+        }
+        return to_points(&positions);
+    }
+
+    // 大円の角度は小円の直径 (+ 余白) に比例して割り当て、
+    // 隣り合う小円が重ならないよう大円の半径を決める
+    let small_radii: Vec<f64> = groups.iter().map(|g| (g.len() as f64).sqrt()).collect();
+    let total: f64 = small_radii.iter().map(|r| r * 2.0 + 1.0).sum();
+    let spans: Vec<f64> = small_radii
+        .iter()
+        .map(|r| (r * 2.0 + 1.0) / total * std::f64::consts::TAU)
+        .collect();
+    let mut big_radius = total / std::f64::consts::TAU;
+    for i in 0..groups.len() {
+        let j = (i + 1) % groups.len();
+        let between = (spans[i] + spans[j]) / 2.0;
+        let chord = 2.0 * (between / 2.0).sin();
+        if chord > 0.0 {
+            big_radius = big_radius.max((small_radii[i] + small_radii[j] + 1.0) / chord);
+        }
+    }
+    let mut angle = 0.0;
+    for ((group, &r), &span) in groups.iter().zip(&small_radii).zip(&spans) {
+        let theta = angle + span / 2.0;
+        angle += span;
+        let center = (big_radius * theta.cos(), big_radius * theta.sin());
+        for (rank, &i) in group.iter().enumerate() {
+            if group.len() == 1 {
+                positions[i] = center;
+            } else {
+                let phi = rank as f64 / group.len() as f64 * std::f64::consts::TAU;
+                positions[i] = (center.0 + r * phi.cos(), center.1 + r * phi.sin());
+            }
+        }
+    }
+    to_points(&positions)
+}
+
+/// 古典的多次元尺度構成法 (Torgerson MDS)
+///
+/// ホップ数の距離行列を二重中心化し、上位 2 つの固有ベクトルをべき乗法で求めて座標とする。
+fn classical_mds(network: &CooccurrenceNetwork, progress: Progress) -> Vec<Point> {
+    let n = network.nodes.len();
+    let distances = graph_distances(network);
+
+    // B = -1/2 J D² J
+    let squared: Vec<Vec<f64>> = distances
+        .iter()
+        .map(|row| row.iter().map(|d| d * d).collect())
+        .collect();
+    let row_means: Vec<f64> = squared
+        .iter()
+        .map(|row| row.iter().sum::<f64>() / n as f64)
+        .collect();
+    let grand_mean = row_means.iter().sum::<f64>() / n as f64;
+    let b: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| -0.5 * (squared[i][j] - row_means[i] - row_means[j] + grand_mean))
+                .collect()
+        })
+        .collect();
+
+    // 正の固有値を持つ上位 2 軸を求める。負の固有値 (非ユークリッド成分) の軸は除いて探し直す
+    let mut found: Vec<Vec<f64>> = Vec::new();
+    let mut axes: Vec<(f64, Vec<f64>)> = Vec::new();
+    for attempt in 0..MDS_MAX_AXES {
+        if axes.len() == 2 {
+            break;
+        }
+        // 決定的な初期ベクトル (定数ベクトルは B の零空間に含まれるため避ける)
+        let mut v: Vec<f64> = (0..n)
+            .map(|i| ((i + 1) as f64 * (attempt as f64 + 1.3)).sin())
+            .collect();
+        let mut eigenvalue = 0.0;
+        for iteration in 0..MDS_ITERATIONS {
+            // 2 軸が見つかるまでの進捗 (探し直す軸の分は含めない)
+            let axis_progress = iteration as f32 / MDS_ITERATIONS as f32;
+            if iteration % 10 == 0 && !progress((axes.len() as f32 + axis_progress) / 2.0) {
+                return to_points(&vec![(0.0, 0.0); n]);
+            }
+            // 既に求めた固有ベクトルの成分を除く
+            for u in &found {
+                let dot: f64 = v.iter().zip(u).map(|(a, b)| a * b).sum();
+                for (x, y) in v.iter_mut().zip(u) {
+                    *x -= dot * y;
+                }
+            }
+            let next: Vec<f64> = b
+                .iter()
+                .map(|row| row.iter().zip(&v).map(|(a, x)| a * x).sum())
+                .collect();
+            let norm = next.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm < 1e-12 {
+                eigenvalue = 0.0;
+                break;
+            }
+            // レイリー商で固有値の符号を得る
+            eigenvalue = v.iter().zip(&next).map(|(a, b)| a * b).sum();
+            let next: Vec<f64> = next.into_iter().map(|x| x / norm).collect();
+            // 負の固有値では反復ごとに符号が反転するため、反転も同じ向きとみなす
+            let change: f64 = next
+                .iter()
+                .zip(&v)
+                .map(|(a, b)| (a - b).abs().min((a + b).abs()))
+                .sum();
+            v = next;
+            if change < 1e-10 {
+                break;
+            }
+        }
+        found.push(v.clone());
+        if eigenvalue > 0.0 {
+            axes.push((eigenvalue, v));
+        }
+    }
+    while axes.len() < 2 {
+        axes.push((0.0, vec![0.0; n]));
+    }
+
+    let positions: Vec<Point> = (0..n)
+        .map(|i| {
+            let coordinate = |axis: usize| {
+                let (lambda, v) = &axes[axis];
+                v[i] * lambda.sqrt()
+            };
+            (coordinate(0) as f32, coordinate(1) as f32)
+        })
+        .collect();
+    // 構造的に同値なノード (同じ距離を持つノード) は同じ座標になるため、重なりを解消する
+    separate_overlaps(positions)
+}
+
+/// 近すぎるノード対を押し離す (全体の広がりに対する最小間隔を保つ)
+fn separate_overlaps(mut positions: Vec<Point>) -> Vec<Point> {
+    let n = positions.len();
+    let extent = positions
+        .iter()
+        .map(|p| (p.0 * p.0 + p.1 * p.1).sqrt())
+        .fold(0.0, f32::max)
+        .max(1.0);
+    let min_distance = extent * OVERLAP_MIN_DISTANCE;
+    for _ in 0..OVERLAP_ITERATIONS {
+        let mut moved = false;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (mut dx, mut dy) = (
+                    positions[j].0 - positions[i].0,
+                    positions[j].1 - positions[i].1,
+                );
+                let mut dist = (dx * dx + dy * dy).sqrt();
+                if dist >= min_distance {
+                    continue;
+                }
+                if dist < 1e-6 {
+                    // 完全に重なっている場合はノード番号から決まる向きへ離す
+                    let angle = (i * 7 + j * 13) as f32;
+                    dx = angle.cos();
+                    dy = angle.sin();
+                    dist = 1.0;
+                }
+                let push = (min_distance - dist) / 2.0;
+                let (ux, uy) = (dx / dist * push, dy / dist * push);
+                positions[i].0 -= ux;
+                positions[i].1 -= uy;
+                positions[j].0 += ux;
+                positions[j].1 += uy;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    positions
+}
+
+/// 重心を原点に移し、最も遠いノードまでの距離が `radius` になるよう拡大縮小する
+fn fit_to_radius(mut positions: Vec<Point>, radius: f32) -> Vec<Point> {
+    let n = positions.len() as f32;
+    let cx = positions.iter().map(|p| p.0).sum::<f32>() / n;
+    let cy = positions.iter().map(|p| p.1).sum::<f32>() / n;
+    let extent = positions
+        .iter()
+        .map(|p| ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt())
+        .fold(0.0, f32::max);
+    let scale = if extent > 0.0 { radius / extent } else { 1.0 };
+    for p in positions.iter_mut() {
+        *p = ((p.0 - cx) * scale, (p.1 - cy) * scale);
+    }
+    positions
+}
+
+fn to_points(positions: &[(f64, f64)]) -> Vec<Point> {
+    positions
+        .iter()
+        .map(|&(x, y)| (x as f32, y as f32))
+        .collect()
+}
+
+/// 再現性のある擬似乱数 (SplitMix64)
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) の一様乱数
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_from_edges;
+
+    /// 0 - 1 - 2 - 3 - 4 - 0 の閉路
+    fn cycle_network() -> CooccurrenceNetwork {
+        let edges: Vec<_> = (0..5).map(|i| (i, (i + 1) % 5, 1.0)).collect();
+        network_from_edges(5, &edges)
+    }
+
+    #[test]
+    fn layout_task_matches_synchronous_layout() {
+        let network = cycle_network();
+        for method in [LayoutMethod::KamadaKawai, LayoutMethod::Mds] {
+            let expected = compute_layout(&network, method, 1, 100.0);
+            let task = LayoutTask::spawn(network.clone(), method, 1, 100.0);
+            let positions = loop {
+                if let Some(positions) = task.try_take().unwrap() {
+                    break positions;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            };
+            assert_eq!(positions, expected);
+        }
+    }
+
+    #[test]
+    fn barnes_hut_without_approximation_is_exact() {
+        let mut rng = SplitMix64(3);
+        let positions: Vec<Point> = (0..40)
+            .map(|_| {
+                (
+                    (rng.next_f64() * 400.0 - 200.0) as f32,
+                    (rng.next_f64() * 400.0 - 200.0) as f32,
+                )
+            })
+            .collect();
+        let k2 = 900.0;
+        let tree = QuadTree::build(&positions);
+        for (i, &p) in positions.iter().enumerate() {
+            let mut exact = (0.0, 0.0);
+            for (j, &q) in positions.iter().enumerate() {
+                if j != i {
+                    add_repulsion(&mut exact, i, p, q, 1.0, k2);
+                }
+            }
+            let force = tree.repulsion(i, p, k2, 0.0);
+            let error = (force.0 - exact.0).hypot(force.1 - exact.1);
+            assert!(
+                error <= 1e-4 * exact.0.hypot(exact.1),
+                "{force:?} != {exact:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fruchterman_reingold_is_reproducible_for_a_seed() {
+        let network = cycle_network();
+        let layout = |seed| fruchterman_reingold(&network, seed, &|_| true);
+        assert_eq!(layout(7), layout(7));
+        assert_ne!(layout(7), layout(8));
+        let method = LayoutMethod::FruchtermanReingold;
+        assert_eq!(
+            compute_layout(&network, method, 7, 100.0),
+            compute_layout(&network, method, 7, 100.0)
+        );
+    }
+}
@@ -4,11 +4,173 @@ use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{Read, Write};
 
+pub mod bunsetsu;
+pub mod centrality;
+pub mod community;
+pub mod compound;
+pub mod frequency;
+pub mod keyness;
+pub mod layout;
+pub mod network_export;
+pub mod network_file;
+pub mod network_image;
+pub mod ngram;
+pub mod pos_filter;
+pub mod project;
+pub mod sentence;
+pub mod stopwords;
+pub mod structure;
+pub mod unknown_words;
+pub mod user_dictionary;
+
+pub use bunsetsu::{assign_bunsetsu, collect_bunsetsu, save_bunsetsu_to_csv, Bunsetsu};
+pub use centrality::{NodeCentrality, NodeMetric};
+pub use community::CommunityMethod;
+pub use compound::{join_compounds, CompoundConfig};
+pub use frequency::{FrequencyEntry, FrequencyFilter, FrequencySortKey};
+pub use keyness::{
+    load_frequency_list, save_keyness_to_csv, KeynessConfig, KeynessEntry, KeynessMeasure,
+    KeynessResult,
+};
+pub use layout::{compute_layout, ForceLayout, ForceLayoutConfig, LayoutMethod, LayoutTask, Point};
+pub use network_export::NetworkFormat;
+pub use network_file::{MatchedLayout, SavedNetwork};
+pub use network_image::{NetworkScene, Rgba, SceneShape, TextAnchor};
+pub use ngram::{save_ngrams_to_csv, NgramConfig, NgramEntry};
+pub use pos_filter::{ipadic_pos_children, PosFilter, IPADIC_POS};
+pub use project::{ConcordanceQuery, NetworkSettings, Project, PROJECT_FILE_VERSION};
+pub use sentence::{
+    save_sentences_to_csv, split_sentence_spans, split_sentences, SentenceSpan,
+    SentenceSplitConfig,
+};
+pub use stopwords::{load_stopwords, parse_stopwords, save_stopwords, StopwordPreset};
+pub use structure::{
+    find_unit, save_unit_frequencies_to_csv, DocumentStructure, Heading, HeadingLevel, Paragraph,
+    StructureConfig, StructureUnit, TextUnit, UnitScope,
+};
+pub use unknown_words::{
+    save_unknown_words_to_csv, CandidateConfig, CandidateKind, DictionaryCandidate, UnknownWord,
+};
+pub use user_dictionary::{
+    classify_correction, load_user_words, save_user_words, CorrectionKind, UserWord,
+};
+
 /// 形態素解析結果を格納する構造体
 #[derive(Debug, Clone)]
 pub struct TokenInfo {
-    pub text: String, // 単語
-    pub pos: String,  // 品詞
+    pub text: String,            // 単語
+    pub pos: String,             // 品詞
+    pub pos_detail: Vec<String>, // 品詞細分類 (IPADIC の細分類1〜3、"*" は除く)
+    pub reading: String,         // 読み (カタカナ、辞書にない語は空)
+    pub features: Vec<String>,   // 辞書の素性すべて (IPADIC では `IPADIC_FEATURES` の順)
+    pub unknown: bool,           // 辞書にない語 (未知語処理で品詞を推定した語)
+    pub bunsetsu: Option<usize>, // 属する文節の番号 (解析時に付ける。空白は None)
+    pub start: usize,            // 解析したテキストでの開始位置 (バイト)
+    pub end: usize,              // 解析したテキストでの終了位置 (バイト, 含まない)
+}
+
+/// IPADIC の素性の名前
+pub const IPADIC_FEATURES: [&str; 9] = [
+    "品詞",
+    "品詞細分類1",
+    "品詞細分類2",
+    "品詞細分類3",
+    "活用型",
+    "活用形",
+    "原形",
+    "読み",
+    "発音",
+];
+
+impl TokenInfo {
+    /// 品詞を細分類まで "-" で連結したもの (例: "名詞-非自立-一般")
+    pub fn pos_path(&self) -> String {
+        std::iter::once(&self.pos)
+            .chain(&self.pos_detail)
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// lindera のトークンから作る
+    fn from_token(token: &mut lindera::token::Token<'_>) -> Self {
+        let pos = token.get_detail(0).unwrap_or("*").to_string();
+        let pos_detail = (1..=3)
+            .map_while(|i| token.get_detail(i).filter(|d| *d != "*").map(|d| d.to_string()))
+            .collect();
+        let reading = token
+            .get_detail(7)
+            .filter(|r| *r != "*")
+            .unwrap_or("")
+            .to_string();
+        let features = token.details().iter().map(|d| d.to_string()).collect();
+        TokenInfo {
+            text: token.surface.to_string(),
+            pos,
+            pos_detail,
+            reading,
+            features,
+            unknown: token.word_id.is_unknown(),
+            bunsetsu: None,
+            start: token.byte_start,
+            end: token.byte_end,
+        }
+    }
+}
+
+/// (表層形, カンマ区切りの IPADIC の素性) の並びから間を空けずに続くトークン列を作る (テスト用)
+#[cfg(test)]
+pub(crate) fn tokens_from_features(words: &[(&str, &str)]) -> Vec<TokenInfo> {
+    let mut start = 0;
+    words
+        .iter()
+        .map(|(text, features)| {
+            let features: Vec<String> = features.split(',').map(|f| f.to_string()).collect();
+            let token = TokenInfo {
+                text: text.to_string(),
+                pos: features[0].clone(),
+                pos_detail: features[1..4]
+                    .iter()
+                    .take_while(|d| *d != "*")
+                    .cloned()
+                    .collect(),
+                reading: features.get(7).filter(|r| *r != "*").cloned().unwrap_or_default(),
+                features,
+                unknown: false,
+                bunsetsu: None,
+                start,
+                end: start + text.len(),
+            };
+            start = token.end;
+            token
+        })
+        .collect()
+}
+
+/// 単語が "0", "1", … のノードを `n` 個持ち、(始点, 終点, 関連度) のエッジを張ったネットワーク
+/// (テスト用。頻度・共起回数はすべて 1)
+#[cfg(test)]
+pub(crate) fn network_from_edges(n: usize, edges: &[(usize, usize, f64)]) -> CooccurrenceNetwork {
+    CooccurrenceNetwork {
+        nodes: (0..n)
+            .map(|i| CooccurrenceNode {
+                word: i.to_string(),
+                frequency: 1,
+                community: 0,
+                centrality: Default::default(),
+            })
+            .collect(),
+        edges: edges
+            .iter()
+            .map(|&(source, target, weight)| CooccurrenceEdge {
+                source,
+                target,
+                count: 1,
+                weight,
+            })
+            .collect(),
+        ..Default::default()
+    }
 }
 
 /// コンコーダンス結果を格納する構造体
@@ -20,24 +182,124 @@ pub struct ConcordanceResult {
     pub line_number: usize,    // 行番号
 }
 
+/// 共起ネットワークのエッジの重み付けに使う関連度指標
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMeasure {
+    #[default]
+    Count, // 共起回数
+    Jaccard, // Jaccard 係数
+    Dice,    // Dice 係数
+    Simpson, // Simpson 係数
+    Pmi,     // 自己相互情報量
+    Npmi,    // 正規化自己相互情報量
+    Cosine,  // コサイン類似度
+}
+
+impl EdgeMeasure {
+    pub const ALL: [EdgeMeasure; 7] = [
+        EdgeMeasure::Count,
+        EdgeMeasure::Jaccard,
+        EdgeMeasure::Dice,
+        EdgeMeasure::Simpson,
+        EdgeMeasure::Pmi,
+        EdgeMeasure::Npmi,
+        EdgeMeasure::Cosine,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EdgeMeasure::Count => "共起回数",
+            EdgeMeasure::Jaccard => "Jaccard",
+            EdgeMeasure::Dice => "Dice",
+            EdgeMeasure::Simpson => "Simpson",
+            EdgeMeasure::Pmi => "PMI",
+            EdgeMeasure::Npmi => "NPMI",
+            EdgeMeasure::Cosine => "コサイン",
+        }
+    }
+
+    /// 共起回数と各語の出現単位数から関連度を計算する
+    ///
+    /// cooc: 共起単位数, freq_a / freq_b: 各語が出現する単位数, units: 全単位数
+    pub fn compute(&self, cooc: usize, freq_a: usize, freq_b: usize, units: usize) -> f64 {
+        let (c, a, b, n) = (cooc as f64, freq_a as f64, freq_b as f64, units as f64);
+        if c == 0.0 || a == 0.0 || b == 0.0 || n == 0.0 {
+            return 0.0;
+        }
+        match self {
+            EdgeMeasure::Count => c,
+            EdgeMeasure::Jaccard => c / (a + b - c),
+            EdgeMeasure::Dice => 2.0 * c / (a + b),
+            EdgeMeasure::Simpson => c / a.min(b),
+            EdgeMeasure::Pmi => (c * n / (a * b)).log2(),
+            EdgeMeasure::Npmi => {
+                let p = c / n;
+                if p >= 1.0 {
+                    1.0
+                } else {
+                    (c * n / (a * b)).log2() / -p.log2()
+                }
+            }
+            EdgeMeasure::Cosine => c / (a * b).sqrt(),
+        }
+    }
+}
+
+/// 共起を数える単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CooccurrenceUnit {
+    #[default]
+    Sentence,      // 文 (。!? および改行で区切る)
+    Paragraph,     // 段落 (空行で区切る)
+    Document,      // 文書 (1 行を 1 文書とする)
+    Window(usize), // N 語の窓 (スライディングウィンドウ)
+}
+
+impl CooccurrenceUnit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CooccurrenceUnit::Sentence => "文",
+            CooccurrenceUnit::Paragraph => "段落 (空行区切り)",
+            CooccurrenceUnit::Document => "文書 (1 行ごと)",
+            CooccurrenceUnit::Window(_) => "N 語の窓",
+        }
+    }
+}
+
 /// 共起ネットワークの計算条件
 #[derive(Debug, Clone)]
 pub struct CooccurrenceConfig {
-    pub target_pos: Vec<String>,    // 対象品詞 (例: ["名詞", "動詞", "形容詞"])
-    pub min_word_freq: usize,       // 最小単語頻度
-    pub min_edge_count: usize,      // 最小共起回数
-    pub max_nodes: usize,           // 最大ノード数 (頻度上位)
-    pub stopwords: HashSet<String>, // 除外単語
+    pub pos_filter: PosFilter,             // 対象品詞 (細分類による対象・除外の指定)
+    pub min_word_freq: usize,              // 最小単語頻度
+    pub min_edge_count: usize,             // 最小共起回数
+    pub max_nodes: usize,                  // 最大ノード数 (頻度上位)
+    pub stopwords: HashSet<String>,        // 除外単語
+    pub edge_measure: EdgeMeasure,         // エッジの重み付けに使う関連度指標
+    pub min_edge_weight: f64,              // 関連度の下限
+    pub max_edges: usize,                  // 最大エッジ数 (関連度上位, 0 で無制限)
+    pub unit: CooccurrenceUnit,            // 共起を数える単位
+    pub community_method: CommunityMethod, // コミュニティ検出の手法
+    pub scope: Option<UnitScope>,          // 対象範囲 (章・節・段落。None でテキスト全体)
 }
 
 impl Default for CooccurrenceConfig {
     fn default() -> Self {
         Self {
-            target_pos: vec!["名詞".to_string(), "動詞".to_string(), "形容詞".to_string()],
+            pos_filter: PosFilter::including(&["名詞", "動詞", "形容詞"]).excluding(&[
+                "名詞-非自立",
+                "名詞-数",
+                "動詞-非自立",
+            ]),
             min_word_freq: 2,
             min_edge_count: 2,
             max_nodes: 60,
             stopwords: HashSet::new(),
+            edge_measure: EdgeMeasure::Count,
+            min_edge_weight: 0.0,
+            max_edges: 0,
+            unit: CooccurrenceUnit::Sentence,
+            community_method: CommunityMethod::Louvain,
+            scope: None,
         }
     }
 }
@@ -47,6 +309,8 @@ impl Default for CooccurrenceConfig {
 pub struct CooccurrenceNode {
     pub word: String,
     pub frequency: usize,
+    pub community: usize,           // コミュニティ番号 (0 が最大のコミュニティ)
+    pub centrality: NodeCentrality, // 中心性指標
 }
 
 /// 共起ネットワークのエッジ
@@ -55,6 +319,7 @@ pub struct CooccurrenceEdge {
     pub source: usize, // ノードインデックス
     pub target: usize, // ノードインデックス
     pub count: usize,  // 共起回数
+    pub weight: f64,   // 関連度 (CooccurrenceConfig::edge_measure による)
 }
 
 /// 共起ネットワーク全体
@@ -62,81 +327,372 @@ pub struct CooccurrenceEdge {
 pub struct CooccurrenceNetwork {
     pub nodes: Vec<CooccurrenceNode>,
     pub edges: Vec<CooccurrenceEdge>,
+    pub edge_measure: EdgeMeasure, // エッジの重み付けに使った指標
+    pub modularity: f64,           // コミュニティ分割のモジュラリティ
+    pub units: usize,              // 共起を数えた単位 (文・段落・文書・窓) の数 (不明なら 0)
 }
 
-/// 入力テキストを文単位に分割する (。!?。改行 を区切りとして使用)
-fn split_sentences(text: &str) -> Vec<String> {
-    let mut sentences = Vec::new();
-    let mut current = String::new();
-    for ch in text.chars() {
-        match ch {
-            '。' | '．' | '!' | '！' | '?' | '？' | '\n' | '\r' => {
-                if !current.trim().is_empty() {
-                    sentences.push(std::mem::take(&mut current));
+impl CooccurrenceNetwork {
+    /// エッジの関連度の最小値・最大値
+    pub fn weight_range(&self) -> Option<(f64, f64)> {
+        let mut weights = self.edges.iter().map(|e| e.weight);
+        let first = weights.next()?;
+        Some(weights.fold((first, first), |(lo, hi), w| (lo.min(w), hi.max(w))))
+    }
+
+    /// エッジの関連度をネットワーク内で 0..=1 に正規化した値
+    pub fn relative_weight(&self, weight: f64, range: Option<(f64, f64)>) -> f32 {
+        match range {
+            Some((lo, hi)) if hi > lo => ((weight - lo) / (hi - lo)) as f32,
+            _ => 1.0,
+        }
+    }
+
+    /// 単語でノードを探す (完全一致を優先し、なければ部分一致の最初のノード)
+    pub fn find_node(&self, query: &str) -> Option<usize> {
+        let query = query.trim();
+        if query.is_empty() {
+            return None;
+        }
+        self.nodes
+            .iter()
+            .position(|n| n.word == query)
+            .or_else(|| self.nodes.iter().position(|n| n.word.contains(query)))
+    }
+
+    /// 指定ノードから `depth` ホップ以内にあるノード (エゴネットワーク)
+    pub fn ego_nodes(&self, center: usize, depth: usize) -> Vec<bool> {
+        let n = self.nodes.len();
+        let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for edge in &self.edges {
+            neighbors[edge.source].push(edge.target);
+            neighbors[edge.target].push(edge.source);
+        }
+        let mut included = vec![false; n];
+        if center >= n {
+            return included;
+        }
+        included[center] = true;
+        let mut frontier = vec![center];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for v in frontier {
+                for &u in &neighbors[v] {
+                    if !included[u] {
+                        included[u] = true;
+                        next.push(u);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        included
+    }
+
+    /// ノード一覧を CSV ファイルに保存 (UTF-8 BOM 付き)
+    pub fn save_nodes_to_csv(&self, path: std::path::PathBuf) -> Result<(), std::io::Error> {
+        let mut file = File::create(path)?;
+
+        // UTF-8 BOMを書き込み（Excel対応）
+        file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+        writeln!(
+            file,
+            "単語,出現頻度,コミュニティ,次数,重み付き次数,媒介中心性,近接中心性,固有ベクトル中心性"
+        )?;
+        for node in &self.nodes {
+            let c = &node.centrality;
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{}",
+                escape_csv_field(&node.word),
+                node.frequency,
+                node.community + 1,
+                c.degree,
+                c.weighted_degree,
+                c.betweenness,
+                c.closeness,
+                c.eigenvector
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// エッジ一覧を CSV ファイルに保存 (UTF-8 BOM 付き)
+    pub fn save_edges_to_csv(&self, path: std::path::PathBuf) -> Result<(), std::io::Error> {
+        let mut file = File::create(path)?;
+
+        // UTF-8 BOMを書き込み（Excel対応）
+        file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+        writeln!(file, "単語1,単語2,共起回数,関連度 ({}),コミュニティ", self.edge_measure.label())?;
+        for edge in &self.edges {
+            let source = &self.nodes[edge.source];
+            let target = &self.nodes[edge.target];
+            // 同じコミュニティ内のエッジのみコミュニティ番号を記録する
+            let community = if source.community == target.community {
+                (source.community + 1).to_string()
+            } else {
+                String::new()
+            };
+            writeln!(
+                file,
+                "{},{},{},{},{}",
+                escape_csv_field(&source.word),
+                escape_csv_field(&target.word),
+                edge.count,
+                edge.weight,
+                community
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 文書の区切り方 (レンジ = 出現文書数 の計算単位)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentSplit {
+    Whole,     // テキスト全体を 1 文書とする
+    BlankLine, // 空行区切り
+    Line,      // 1 行 = 1 文書
+}
+
+impl DocumentSplit {
+    pub const ALL: [DocumentSplit; 3] = [
+        DocumentSplit::Whole,
+        DocumentSplit::BlankLine,
+        DocumentSplit::Line,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DocumentSplit::Whole => "テキスト全体",
+            DocumentSplit::BlankLine => "空行区切り",
+            DocumentSplit::Line => "1 行ごと",
+        }
+    }
+}
+
+/// 入力テキストを文書単位に分割する (空の文書は除く)
+pub fn split_documents(text: &str, split: DocumentSplit) -> Vec<String> {
+    let documents: Vec<String> = match split {
+        DocumentSplit::Whole => vec![text.to_string()],
+        DocumentSplit::Line => text.lines().map(|l| l.to_string()).collect(),
+        DocumentSplit::BlankLine => {
+            let mut documents = Vec::new();
+            let mut current = String::new();
+            for line in text.lines() {
+                if line.trim().is_empty() {
+                    if !current.is_empty() {
+                        documents.push(std::mem::take(&mut current));
+                    }
                 } else {
-                    current.clear();
+                    if !current.is_empty() {
+                        current.push('\n');
+                    }
+                    current.push_str(line);
                 }
             }
-            _ => current.push(ch),
+            if !current.is_empty() {
+                documents.push(current);
+            }
+            documents
+        }
+    };
+    documents
+        .into_iter()
+        .filter(|d| !d.trim().is_empty())
+        .collect()
+}
+
+/// 共起を数える単位 (窓以外) でテキストを分割する
+fn split_cooccurrence_units(text: &str, unit: CooccurrenceUnit) -> Vec<String> {
+    match unit {
+        CooccurrenceUnit::Sentence => split_sentences(text),
+        CooccurrenceUnit::Paragraph => split_documents(text, DocumentSplit::BlankLine),
+        CooccurrenceUnit::Document => split_documents(text, DocumentSplit::Line),
+        CooccurrenceUnit::Window(_) => split_documents(text, DocumentSplit::Whole),
+    }
+}
+
+/// CSV のフィールドをエスケープする (カンマ・引用符・改行を含む場合は引用符で囲む)
+pub(crate) fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// CSV の 1 行をフィールドに分ける (`escape_csv_field` で囲んだ引用符・区切り文字に対応)
+pub(crate) fn split_csv_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// 形態素解析の分割モード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SegmentMode {
+    #[default]
+    Normal, // 辞書どおりに分割
+    Decompose, // 長い複合語をさらに分割
+}
+
+impl SegmentMode {
+    pub const ALL: [SegmentMode; 2] = [SegmentMode::Normal, SegmentMode::Decompose];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SegmentMode::Normal => "通常",
+            SegmentMode::Decompose => "複合語を分割",
         }
     }
-    if !current.trim().is_empty() {
-        sentences.push(current);
+
+    /// lindera の設定での名前
+    fn key(&self) -> &'static str {
+        match self {
+            SegmentMode::Normal => "normal",
+            SegmentMode::Decompose => "decompose",
+        }
+    }
+}
+
+/// 形態素解析器の設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenizerSettings {
+    pub mode: SegmentMode,
+    pub user_dictionary: Option<String>, // ユーザー辞書 (CSV) のパス
+    pub compound: CompoundConfig,        // 解析後に複合名詞をまとめる規則
+}
+
+impl TokenizerSettings {
+    /// 設定どおりの形態素解析器を作る
+    fn build(&self) -> Result<Tokenizer, Box<dyn std::error::Error>> {
+        let mut segmenter = json!({
+            "dictionary": "embedded://ipadic",
+            "mode": self.mode.key()
+        });
+        if let Some(path) = &self.user_dictionary {
+            segmenter["user_dictionary"] = json!(path);
+        }
+        let config: TokenizerConfig = json!({
+            "segmenter": segmenter,
+            "character_filters": [],
+            "token_filters": []
+        });
+        Ok(Tokenizer::from_config(&config)?)
     }
-    sentences
 }
 
 /// アプリケーションのメインロジックを管理する構造体
 pub struct TokenizerCore {
     pub tokenizer: Tokenizer,
+    pub settings: TokenizerSettings, // `tokenizer` を作った設定
     pub input_text: String,
     pub tokens: Vec<TokenInfo>,
-    pub word_frequencies: HashMap<String, usize>,
+    pub word_frequencies: HashMap<String, usize>, // ストップワードを除いた単語頻度
     pub file_path: Option<String>,
+    pub stopwords: HashSet<String>, // 頻度表・CSV・n-gram・共起ネットワーク・特徴語から除外する語
+    pub structure_config: StructureConfig, // 見出しの検出パターン
+    pub structure: DocumentStructure,      // 解析時点のテキストの章・節・段落
+    pub revision: u64,                     // 解析結果・ストップワード・文書構造の変更ごとに増える
 }
 
 impl TokenizerCore {
     /// 新しいTokenizerCoreインスタンスを作成
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let config: TokenizerConfig = json!({
-            "segmenter": {
-                "dictionary": "embedded://ipadic",
-                "mode": "normal"
-            },
-            "character_filters": [],
-            "token_filters": []
-        });
+        Self::with_settings(TokenizerSettings::default())
+    }
 
+    /// 設定を指定してTokenizerCoreインスタンスを作成
+    pub fn with_settings(settings: TokenizerSettings) -> Result<Self, Box<dyn std::error::Error>> {
         // Tokenizerの初期化
-        let tokenizer = Tokenizer::from_config(&config)?;
+        let tokenizer = settings.build()?;
 
         Ok(Self {
             tokenizer,
+            settings,
             input_text: String::new(),
             tokens: Vec::new(),
             word_frequencies: HashMap::new(),
             file_path: None,
+            stopwords: HashSet::new(),
+            structure_config: StructureConfig::default(),
+            structure: DocumentStructure::default(),
+            revision: 0,
         })
     }
 
+    /// 形態素解析器の設定を変更する (解析器を作れなければ変更しない)
+    ///
+    /// 解析結果は変わらないため、必要なら `analyze_text` で解析し直す。
+    pub fn set_tokenizer_settings(
+        &mut self,
+        settings: TokenizerSettings,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.tokenizer = settings.build()?;
+        self.settings = settings;
+        Ok(())
+    }
+
     /// テキストを形態素解析する
     pub fn analyze_text(&mut self) {
         self.tokens.clear();
         self.word_frequencies.clear();
 
-        if let Ok(tokens) = self.tokenizer.tokenize(&self.input_text) {
-            for mut token in tokens {
-                let text = token.surface.to_string();
-                let pos = token.get_detail(0).unwrap_or("*").to_string();
-
-                self.tokens.push(TokenInfo {
-                    text: text.clone(),
-                    pos,
-                });
+        self.tokens = self.tokenize_to_infos(&self.input_text);
+        assign_bunsetsu(&mut self.tokens);
+        self.recount_frequencies();
+        // 検出パターンは設定時に検証済み
+        self.structure =
+            DocumentStructure::parse(&self.input_text, &self.structure_config).unwrap_or_default();
+    }
 
-                *self.word_frequencies.entry(text).or_insert(0) += 1;
+    /// テキストを形態素解析して TokenInfo の列を返す (解析結果の状態は変更しない)
+    ///
+    /// 設定で有効なら連続する名詞を複合名詞にまとめる。
+    pub fn tokenize_to_infos(&self, text: &str) -> Vec<TokenInfo> {
+        let mut infos = Vec::new();
+        if let Ok(tokens) = self.tokenizer.tokenize(text) {
+            for mut token in tokens {
+                infos.push(TokenInfo::from_token(&mut token));
             }
         }
+        join_compounds(infos, &self.settings.compound)
+    }
+
+    /// 共起ネットワークの対象語を抽出する (対象外のトークンは None)
+    fn cooccurrence_words(&self, text: &str, config: &CooccurrenceConfig) -> Vec<Option<String>> {
+        self.tokenize_to_infos(text)
+            .into_iter()
+            .filter(|t| !t.text.trim().is_empty())
+            .map(|t| {
+                let target = config.pos_filter.matches(&t.pos_path())
+                    && !config.stopwords.contains(&t.text)
+                    && !self.is_stopword(&t.text);
+                target.then_some(t.text)
+            })
+            .collect()
     }
 
     /// ファイルからテキストを読み込む
@@ -161,12 +717,20 @@ impl TokenizerCore {
         file.write_all(&[0xEF, 0xBB, 0xBF])?;
 
         // ヘッダーを書き込み
-        writeln!(file, "単語,品詞,出現頻度")?;
+        writeln!(file, "単語,品詞,出現頻度,文節番号")?;
 
         // データを書き込み
-        for info in &self.tokens {
+        for info in self.tokens.iter().filter(|t| !self.is_stopword(&t.text)) {
             let frequency = self.word_frequencies.get(&info.text).unwrap_or(&0);
-            writeln!(file, "{},{},{}", info.text, info.pos, frequency)?;
+            let bunsetsu = info.bunsetsu.map(|b| (b + 1).to_string()).unwrap_or_default();
+            writeln!(
+                file,
+                "{},{},{},{}",
+                escape_csv_field(&info.text),
+                escape_csv_field(&info.pos),
+                frequency,
+                bunsetsu
+            )?;
         }
 
         Ok(())
@@ -174,34 +738,41 @@ impl TokenizerCore {
 
     /// 共起ネットワークを計算する
     ///
-    /// 文単位 (。!? および改行で区切る) で同一文に出現する単語ペアの共起回数を集計する。
-    /// 同じ文内で同一単語ペアが複数回出現しても 1 回としてカウントする。
+    /// `unit` で指定した単位 (文・段落・文書 (1 行)・N 語の窓) ごとに、同一単位に出現する
+    /// 単語ペアの共起回数を集計する。同じ単位内で同一単語ペアが複数回出現しても 1 回としてカウントする。
+    /// エッジは `edge_measure` の関連度で重み付けし、最小共起回数・関連度の下限で絞り込んだ上で
+    /// 関連度の降順に `max_edges` 件まで残す。最後に `community_method` でコミュニティを検出し、
+    /// 各ノードの中心性を計算する。`scope` を指定した場合はその章・節・段落のみを対象にする。
     pub fn compute_cooccurrence(&self, config: &CooccurrenceConfig) -> CooccurrenceNetwork {
-        let sentences = split_sentences(&self.input_text);
-
-        let mut sentence_words: Vec<HashSet<String>> = Vec::with_capacity(sentences.len());
+        let text = self.scoped_text(config.scope.as_ref());
+        let mut unit_words: Vec<HashSet<String>> = Vec::new();
         let mut word_freq: HashMap<String, usize> = HashMap::new();
 
-        for sentence in &sentences {
-            let mut words_in_sentence: HashSet<String> = HashSet::new();
-            if let Ok(tokens) = self.tokenizer.tokenize(sentence) {
-                for mut token in tokens {
-                    let pos = token.get_detail(0).unwrap_or("*").to_string();
-                    if !config.target_pos.iter().any(|p| p == &pos) {
-                        continue;
-                    }
-                    let word = token.surface.to_string();
-                    if word.trim().is_empty() {
-                        continue;
+        match config.unit {
+            CooccurrenceUnit::Window(size) => {
+                // 対象テキスト全体のトークン列上で size 語の窓をずらしながら集計する
+                let words = self.cooccurrence_words(&text, config);
+                for word in words.iter().flatten() {
+                    *word_freq.entry(word.clone()).or_insert(0) += 1;
+                }
+                let size = size.max(2);
+                if words.len() <= size {
+                    unit_words.push(words.into_iter().flatten().collect());
+                } else {
+                    for window in words.windows(size) {
+                        unit_words.push(window.iter().flatten().cloned().collect());
                     }
-                    if config.stopwords.contains(&word) {
-                        continue;
+                }
+            }
+            unit => {
+                for segment in split_cooccurrence_units(&text, unit) {
+                    let words = self.cooccurrence_words(&segment, config);
+                    for word in words.iter().flatten() {
+                        *word_freq.entry(word.clone()).or_insert(0) += 1;
                     }
-                    *word_freq.entry(word.clone()).or_insert(0) += 1;
-                    words_in_sentence.insert(word);
+                    unit_words.push(words.into_iter().flatten().collect());
                 }
             }
-            sentence_words.push(words_in_sentence);
         }
 
         let mut sorted: Vec<(String, usize)> = word_freq
@@ -213,7 +784,12 @@ impl TokenizerCore {
 
         let nodes: Vec<CooccurrenceNode> = sorted
             .into_iter()
-            .map(|(word, frequency)| CooccurrenceNode { word, frequency })
+            .map(|(word, frequency)| CooccurrenceNode {
+                word,
+                frequency,
+                community: 0,
+                centrality: NodeCentrality::default(),
+            })
             .collect();
 
         let word_to_idx: HashMap<&str, usize> = nodes
@@ -223,13 +799,18 @@ impl TokenizerCore {
             .collect();
 
         let mut edge_counts: HashMap<(usize, usize), usize> = HashMap::new();
-        for words in &sentence_words {
+        // 各ノードが出現する単位の数 (関連度の計算用)
+        let mut unit_freq = vec![0usize; nodes.len()];
+        for words in &unit_words {
             let mut indices: Vec<usize> = words
                 .iter()
                 .filter_map(|w| word_to_idx.get(w.as_str()).copied())
                 .collect();
             indices.sort_unstable();
             indices.dedup();
+            for &i in &indices {
+                unit_freq[i] += 1;
+            }
             for i in 0..indices.len() {
                 for j in (i + 1)..indices.len() {
                     let key = (indices[i], indices[j]);
@@ -238,6 +819,7 @@ impl TokenizerCore {
             }
         }
 
+        let units = unit_words.len();
         let mut edges: Vec<CooccurrenceEdge> = edge_counts
             .into_iter()
             .filter(|(_, c)| *c >= config.min_edge_count)
@@ -245,11 +827,32 @@ impl TokenizerCore {
                 source: s,
                 target: t,
                 count,
+                weight: config
+                    .edge_measure
+                    .compute(count, unit_freq[s], unit_freq[t], units),
             })
+            .filter(|e| e.weight >= config.min_edge_weight)
             .collect();
-        edges.sort_by(|a, b| b.count.cmp(&a.count));
+        edges.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then_with(|| b.count.cmp(&a.count))
+                .then_with(|| (a.source, a.target).cmp(&(b.source, b.target)))
+        });
+        if config.max_edges > 0 {
+            edges.truncate(config.max_edges);
+        }
 
-        CooccurrenceNetwork { nodes, edges }
+        let mut network = CooccurrenceNetwork {
+            nodes,
+            edges,
+            edge_measure: config.edge_measure,
+            modularity: 0.0,
+            units,
+        };
+        network.detect_communities(config.community_method);
+        network.compute_centrality();
+        network
     }
 
     /// コンコーダンス検索を実行
@@ -258,39 +861,34 @@ impl TokenizerCore {
         let lines: Vec<&str> = self.input_text.lines().collect();
 
         for (line_num, line) in lines.iter().enumerate() {
-            if let Ok(tokens) = self.tokenizer.tokenize(line) {
-                for (i, token) in tokens.iter().enumerate() {
-                    if token.surface == keyword {
-                        let mut left_context = String::new();
-                        let mut right_context = String::new();
-
-                        // 左文脈の取得
-                        let start = if i > context_size {
-                            i - context_size
-                        } else {
-                            0
-                        };
-                        for t in &tokens[start..i] {
-                            left_context.push_str(&t.surface);
-                        }
-
-                        // 右文脈の取得
-                        let end = if i + context_size < tokens.len() {
-                            i + context_size
-                        } else {
-                            tokens.len()
-                        };
-                        for t in &tokens[i + 1..end] {
-                            right_context.push_str(&t.surface);
-                        }
-
-                        results.push(ConcordanceResult {
-                            keyword: keyword.to_string(),
-                            left_context,
-                            right_context,
-                            line_number: line_num + 1,
-                        });
+            let tokens = self.tokenize_to_infos(line);
+            for (i, token) in tokens.iter().enumerate() {
+                if token.text == keyword {
+                    let mut left_context = String::new();
+                    let mut right_context = String::new();
+
+                    // 左文脈の取得
+                    let start = i.saturating_sub(context_size);
+                    for t in &tokens[start..i] {
+                        left_context.push_str(&t.text);
                     }
+
+                    // 右文脈の取得
+                    let end = if i + context_size < tokens.len() {
+                        i + context_size
+                    } else {
+                        tokens.len()
+                    };
+                    for t in &tokens[i + 1..end] {
+                        right_context.push_str(&t.text);
+                    }
+
+                    results.push(ConcordanceResult {
+                        keyword: keyword.to_string(),
+                        left_context,
+                        right_context,
+                        line_number: line_num + 1,
+                    });
                 }
             }
         }
@@ -298,3 +896,63 @@ impl TokenizerCore {
         results
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_unit_splits_text_by_line() {
+        let text = "猫が鳴く\n犬が走る\n鳥が飛ぶ";
+        let units = split_cooccurrence_units(text, CooccurrenceUnit::Document);
+        assert_eq!(units, vec!["猫が鳴く", "犬が走る", "鳥が飛ぶ"]);
+        assert_eq!(split_cooccurrence_units(text, CooccurrenceUnit::Window(5)).len(), 1);
+    }
+
+    #[test]
+    fn edge_measures_match_their_formulas() {
+        // 20 単位中、語 A が 4 単位・語 B が 5 単位に現れ、2 単位で共起
+        let expected = [
+            (EdgeMeasure::Count, 2.0),
+            (EdgeMeasure::Jaccard, 2.0 / 7.0),
+            (EdgeMeasure::Dice, 4.0 / 9.0),
+            (EdgeMeasure::Simpson, 0.5),
+            (EdgeMeasure::Pmi, 1.0),
+            (EdgeMeasure::Npmi, 1.0 / 10f64.log2()),
+            (EdgeMeasure::Cosine, 2.0 / 20f64.sqrt()),
+        ];
+        for (measure, value) in expected {
+            let actual = measure.compute(2, 4, 5, 20);
+            assert!((actual - value).abs() < 1e-12, "{measure:?}: {actual} != {value}");
+            // 共起しない・単位がない場合は 0
+            assert_eq!(measure.compute(0, 4, 5, 20), 0.0);
+            assert_eq!(measure.compute(2, 4, 5, 0), 0.0);
+        }
+        // すべての単位で共起すると PMI は 0、NPMI は最大値 1
+        assert_eq!(EdgeMeasure::Pmi.compute(3, 3, 3, 3), 0.0);
+        assert_eq!(EdgeMeasure::Npmi.compute(3, 3, 3, 3), 1.0);
+    }
+
+    #[test]
+    fn find_node_prefers_exact_match() {
+        let mut network = network_from_edges(3, &[]);
+        for (node, word) in network.nodes.iter_mut().zip(["東京都", "東京", "京都"]) {
+            node.word = word.to_string();
+        }
+        assert_eq!(network.find_node(" 東京 "), Some(1));
+        assert_eq!(network.find_node("京"), Some(0));
+        assert_eq!(network.find_node("大阪"), None);
+        assert_eq!(network.find_node(""), None);
+    }
+
+    #[test]
+    fn ego_nodes_are_within_the_given_hops() {
+        // 0 - 1 - 2 - 3 のパスグラフと孤立ノード 4
+        let network = network_from_edges(5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+        assert_eq!(network.ego_nodes(1, 0), [false, true, false, false, false]);
+        assert_eq!(network.ego_nodes(1, 1), [true, true, true, false, false]);
+        assert_eq!(network.ego_nodes(0, 5), [true, true, true, true, false]);
+        assert_eq!(network.ego_nodes(4, 2), [false, false, false, false, true]);
+        assert_eq!(network.ego_nodes(9, 1), [false; 5]);
+    }
+}
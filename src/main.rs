@@ -1,9 +1,22 @@
+mod views;
+
 use eframe::egui;
 use jp_word_segment::{
-    ConcordanceResult, CooccurrenceConfig, CooccurrenceNetwork, TokenizerCore,
+    compute_layout, CommunityMethod, CooccurrenceConfig, CooccurrenceNetwork, CooccurrenceUnit,
+    EdgeMeasure, ForceLayout, LayoutMethod, LayoutTask, NetworkFormat, NetworkScene, NetworkSettings,
+    NodeMetric, Point, PosFilter, Project, Rgba, SavedNetwork, SceneShape, StructureUnit,
+    TextAnchor, TokenizerCore, UnitScope,
 };
 use rfd::FileDialog;
 use std::collections::HashSet;
+use views::{
+    pos_filter_ui, virtual_table, AnnotatedTextView, BunsetsuView, ConcordanceView, FrequencyView,
+    KeynessView, NgramView, SentenceView, StopwordView, StructureView, TableColumn,
+    TokenizerSettingsView, UnknownWordView,
+};
+
+/// 埋め込みフォント (画面表示と画像の書き出しで共用)
+const NOTO_SANS_JP: &[u8] = include_bytes!("../assets/Noto_Sans_JP/static/NotoSansJP-Regular.ttf");
 
 fn main() -> Result<(), eframe::Error> {
     let native_options = eframe::NativeOptions::default();
@@ -19,11 +32,22 @@ fn main() -> Result<(), eframe::Error> {
 /// GUIアプリケーションの構造体
 struct TokenizerApp {
     core: TokenizerCore,
-    search_keyword: String,
-    context_size: usize,
-    concordance_results: Vec<ConcordanceResult>,
-    show_concordance: bool,
+    project_path: Option<std::path::PathBuf>, // 開いている・保存したプロジェクトファイル
+    token_rows: Vec<usize>,           // 表に表示するトークンの添字 (ストップワードを除く)
+    token_rows_revision: Option<u64>, // `token_rows` を作った TokenizerCore の revision
+    annotated_text_view: AnnotatedTextView,
+    unknown_word_view: UnknownWordView,
+    frequency_view: FrequencyView,
+    concordance_view: ConcordanceView,
+    open_concordance: bool, // 次のフレームでコンコーダンス検索のセクションを開く
     network_view: NetworkView,
+    ngram_view: NgramView,
+    keyness_view: KeynessView,
+    stopword_view: StopwordView,
+    sentence_view: SentenceView,
+    bunsetsu_view: BunsetsuView,
+    structure_view: StructureView,
+    tokenizer_settings_view: TokenizerSettingsView,
 }
 
 impl TokenizerApp {
@@ -33,9 +57,7 @@ impl TokenizerApp {
         let mut fonts = egui::FontDefinitions::default();
         fonts.font_data.insert(
             "noto_sans_jp".to_owned(),
-            egui::FontData::from_static(include_bytes!(
-                "../assets/Noto_Sans_JP/static/NotoSansJP-Regular.ttf"
-            )),
+            egui::FontData::from_static(NOTO_SANS_JP),
         );
         fonts
             .families
@@ -47,11 +69,63 @@ impl TokenizerApp {
 
         Self {
             core: TokenizerCore::new().expect("Failed to initialize TokenizerCore"),
-            search_keyword: String::new(),
-            context_size: 5,
-            concordance_results: Vec::new(),
-            show_concordance: false,
+            project_path: None,
+            token_rows: Vec::new(),
+            token_rows_revision: None,
+            annotated_text_view: AnnotatedTextView::default(),
+            unknown_word_view: UnknownWordView::default(),
+            frequency_view: FrequencyView::default(),
+            concordance_view: ConcordanceView::default(),
+            open_concordance: false,
             network_view: NetworkView::default(),
+            ngram_view: NgramView::default(),
+            keyness_view: KeynessView::default(),
+            stopword_view: StopwordView::default(),
+            sentence_view: SentenceView::default(),
+            bunsetsu_view: BunsetsuView::default(),
+            structure_view: StructureView::default(),
+            tokenizer_settings_view: TokenizerSettingsView::default(),
+        }
+    }
+
+    /// 現在の状態をプロジェクトとしてファイルに保存する
+    fn save_project(&mut self, path: std::path::PathBuf) -> std::io::Result<()> {
+        let mut project = Project::from_core(&self.core);
+        project.concordance = self.concordance_view.query();
+        project.network = self.network_view.settings();
+        project.save(path.clone())?;
+        self.project_path = Some(path);
+        Ok(())
+    }
+
+    /// プロジェクトファイルを開いて状態を復元する
+    ///
+    /// 形態素解析器の設定を適用できなかった場合も、既定の設定で残りを復元した上でエラーを返す。
+    fn open_project(&mut self, path: std::path::PathBuf) -> std::io::Result<()> {
+        let project = Project::load(path.clone())?;
+        let result = project.restore_core(&mut self.core);
+        self.concordance_view.restore(project.concordance, &self.core);
+        self.network_view.restore_settings(project.network, &self.core);
+        self.project_path = Some(path);
+        result
+    }
+
+    /// 保存先を選んでプロジェクトを保存する
+    fn save_project_as(&mut self) {
+        let file_name = self
+            .project_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "project.json".to_string());
+        if let Some(path) = FileDialog::new()
+            .add_filter("プロジェクト", &["json"])
+            .set_file_name(&file_name)
+            .save_file()
+        {
+            if let Err(e) = self.save_project(path) {
+                eprintln!("プロジェクト保存エラー: {}", e);
+            }
         }
     }
 }
@@ -61,6 +135,36 @@ impl eframe::App for TokenizerApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("日本語形態素解析");
 
+            // プロジェクトの読み込み・保存ボタン
+            ui.horizontal(|ui| {
+                if ui.button("プロジェクトを開く").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("プロジェクト", &["json"])
+                        .pick_file()
+                    {
+                        if let Err(e) = self.open_project(path) {
+                            eprintln!("プロジェクト読み込みエラー: {}", e);
+                        }
+                    }
+                }
+                if ui.button("プロジェクトを保存").clicked() {
+                    match self.project_path.clone() {
+                        Some(path) => {
+                            if let Err(e) = self.save_project(path) {
+                                eprintln!("プロジェクト保存エラー: {}", e);
+                            }
+                        }
+                        None => self.save_project_as(),
+                    }
+                }
+                if ui.button("名前を付けて保存").clicked() {
+                    self.save_project_as();
+                }
+                if let Some(path) = &self.project_path {
+                    ui.label(format!("プロジェクト: {}", path.display()));
+                }
+            });
+
             // ファイル読み込みボタン
             if ui.button("ファイルを開く").clicked() {
                 if let Some(path) = FileDialog::new().pick_file() {
@@ -84,65 +188,98 @@ impl eframe::App for TokenizerApp {
             }
 
             // CSV保存ボタン
-            if !self.core.tokens.is_empty() {
-                if ui.button("CSVファイルに保存").clicked() {
-                    if let Some(path) = FileDialog::new()
-                        .add_filter("CSV", &["csv"])
-                        .set_file_name("morphological_analysis.csv")
-                        .save_file()
-                    {
-                        if let Err(e) = self.core.save_to_csv(path) {
-                            eprintln!("CSV保存エラー: {}", e);
-                        }
+            if !self.core.tokens.is_empty() && ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("morphological_analysis.csv")
+                    .save_file()
+                {
+                    if let Err(e) = self.core.save_to_csv(path) {
+                        eprintln!("CSV保存エラー: {}", e);
                     }
                 }
             }
 
             ui.separator();
 
+            // 形態素解析器の設定セクション
+            ui.collapsing("形態素解析器の設定", |ui| {
+                self.tokenizer_settings_view.ui(ui, &mut self.core);
+            });
+
+            ui.separator();
+
+            // ストップワードセクション
+            ui.collapsing("ストップワード", |ui| {
+                self.stopword_view.ui(ui, &mut self.core);
+            });
+
+            ui.separator();
+
+            // 品詞の色分け表示セクション
+            ui.collapsing("解析結果のテキスト表示 (品詞で色分け)", |ui| {
+                self.annotated_text_view.ui(ui, &mut self.core);
+            });
+
+            ui.separator();
+
+            // 未知語・辞書登録候補セクション
+            ui.collapsing("未知語と辞書登録候補", |ui| {
+                self.unknown_word_view.ui(ui, &mut self.core);
+            });
+
+            ui.separator();
+
+            // 頻度表セクション
+            ui.collapsing("頻度表", |ui| {
+                if let Some(word) = self.frequency_view.ui(ui, &self.core) {
+                    self.concordance_view.search_word(&word, &self.core);
+                    self.open_concordance = true;
+                }
+            });
+
+            ui.separator();
+
             // コンコーダンス検索セクション
-            ui.collapsing("コンコーダンス検索", |ui| {
-                ui.horizontal(|ui| {
-                    ui.label("検索キーワード:");
-                    ui.text_edit_singleline(&mut self.search_keyword);
-                    ui.label("文脈サイズ:");
-                    ui.add(
-                        egui::DragValue::new(&mut self.context_size)
-                            .speed(1.0)
-                            .clamp_range(1..=20),
-                    );
+            egui::CollapsingHeader::new("コンコーダンス検索")
+                .open(std::mem::take(&mut self.open_concordance).then_some(true))
+                .show(ui, |ui| {
+                    self.concordance_view.ui(ui, &self.core);
                 });
 
-                if ui.button("検索").clicked() && !self.search_keyword.is_empty() {
-                    self.concordance_results = self
-                        .core
-                        .search_concordance(&self.search_keyword, self.context_size);
-                    self.show_concordance = true;
-                }
+            ui.separator();
 
-                if self.show_concordance {
-                    ui.label(format!("検索結果: {}件", self.concordance_results.len()));
-
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        egui::Grid::new("concordance_grid")
-                            .striped(true)
-                            .show(ui, |ui| {
-                                ui.heading("行番号");
-                                ui.heading("左文脈");
-                                ui.heading("キーワード");
-                                ui.heading("右文脈");
-                                ui.end_row();
-
-                                for result in &self.concordance_results {
-                                    ui.label(result.line_number.to_string());
-                                    ui.label(&result.left_context);
-                                    ui.label(&result.keyword);
-                                    ui.label(&result.right_context);
-                                    ui.end_row();
-                                }
-                            });
-                    });
-                }
+            // 文書構造セクション
+            ui.collapsing("文書構造 (章・節・段落)", |ui| {
+                self.structure_view.ui(ui, &mut self.core);
+            });
+
+            ui.separator();
+
+            // 文分割セクション
+            ui.collapsing("文分割", |ui| {
+                self.sentence_view.ui(ui, &self.core);
+            });
+
+            ui.separator();
+
+            // 文節セクション
+            ui.collapsing("文節", |ui| {
+                self.bunsetsu_view.ui(ui, &self.core);
+            });
+
+            ui.separator();
+
+            // n-gram セクション
+            ui.collapsing("n-gram (語連鎖)", |ui| {
+                self.ngram_view.ui(ui, &self.core);
+            });
+
+            ui.separator();
+
+            // キーネス分析セクション
+            ui.collapsing("特徴語 (キーネス) 分析", |ui| {
+                self.keyness_view.ui(ui, &self.core);
             });
 
             ui.separator();
@@ -151,102 +288,176 @@ impl eframe::App for TokenizerApp {
             ui.collapsing("共起ネットワーク", |ui| {
                 self.network_view.ui(ui, &self.core);
                 // セクションが開いている時のみ再描画を要求
-                if self.network_view.simulating && self.network_view.network.is_some() {
+                let view = &self.network_view;
+                if (view.simulating || view.layout_task.is_some()) && view.network.is_some() {
                     ui.ctx().request_repaint();
                 }
             });
 
             ui.separator();
 
-            // 解析結果の表示
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                egui::Grid::new("tokens_grid").striped(true).show(ui, |ui| {
-                    ui.heading("単語");
-                    ui.heading("品詞");
-                    ui.heading("頻度");
-                    ui.end_row();
-
-                    for info in &self.core.tokens {
-                        ui.label(&info.text);
-                        ui.label(&info.pos);
-                        ui.label(
-                            self.core
-                                .word_frequencies
-                                .get(&info.text)
-                                .unwrap_or(&0)
-                                .to_string(),
-                        );
-                        ui.end_row();
-                    }
-                });
-            });
+            // 解析結果の表示 (見えている行だけを描画する)
+            if self.token_rows_revision != Some(self.core.revision) {
+                self.token_rows = (0..self.core.tokens.len())
+                    .filter(|&i| !self.core.is_stopword(&self.core.tokens[i].text))
+                    .collect();
+                self.token_rows_revision = Some(self.core.revision);
+            }
+            let columns = [
+                TableColumn::new("単語", 200.0),
+                TableColumn::new("品詞", 200.0),
+                TableColumn::new("頻度", 80.0),
+                TableColumn::new("文節番号", 0.0),
+            ];
+            virtual_table(
+                ui,
+                "tokens_table",
+                &columns,
+                self.token_rows.len(),
+                f32::INFINITY,
+                |row, index| {
+                    let info = &self.core.tokens[self.token_rows[index]];
+                    row.cell(info.text.as_str());
+                    row.cell(info.pos.as_str());
+                    row.cell(
+                        self.core
+                            .word_frequencies
+                            .get(&info.text)
+                            .unwrap_or(&0)
+                            .to_string(),
+                    );
+                    row.cell(info.bunsetsu.map(|b| (b + 1).to_string()).unwrap_or_default());
+                },
+            );
         });
     }
 }
 
+/// ノードの塗り分け方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeColorMode {
+    Community,          // コミュニティごとの色
+    Metric(NodeMetric), // 指標の値のグラデーション
+}
+
+impl NodeColorMode {
+    fn label(&self) -> &'static str {
+        match self {
+            NodeColorMode::Community => "コミュニティ",
+            NodeColorMode::Metric(metric) => metric.label(),
+        }
+    }
+}
+
+/// 中心性の表の並べ替え基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeSortKey {
+    Word,
+    Community,
+    Metric(NodeMetric),
+}
+
 /// 共起ネットワークの表示・操作を行うビュー
 struct NetworkView {
     network: Option<CooccurrenceNetwork>,
-    positions: Vec<egui::Vec2>,    // ワールド座標 (中心0,0)
-    velocities: Vec<egui::Vec2>,
+    network_config: CooccurrenceConfig, // 現在のネットワークを計算した条件
+    layout: ForceLayout, // ワールド座標 (中心0,0) の配置
+    layout_method: LayoutMethod,
+    layout_seed: u64,
+    layout_task: Option<LayoutTask>, // 別スレッドで計算中の静的な配置
     /// 設定 UI 用フィールド
-    pos_nouns: bool,
-    pos_verbs: bool,
-    pos_adjectives: bool,
-    pos_adverbs: bool,
+    pos_filter: PosFilter,
     min_word_freq: usize,
     min_edge_count: usize,
     max_nodes: usize,
     stopwords_input: String,
+    edge_measure: EdgeMeasure,
+    min_edge_weight: f64,
+    max_edges: usize,
+    unit: CooccurrenceUnit,
+    window_size: usize,
+    community_method: CommunityMethod,
+    scope_unit: Option<StructureUnit>, // 対象範囲の単位 (None でテキスト全体)
+    scope_label: String,
     /// 表示状態
+    color_mode: NodeColorMode,
+    size_metric: NodeMetric,
+    show_table: bool,
+    sort_key: NodeSortKey,
+    sort_descending: bool,
+    export_format: NetworkFormat,
+    image_width: u32,
+    image_height: u32,
+    embed_font: bool,
+    canvas_size: egui::Vec2,
     simulating: bool,
     zoom: f32,
     pan: egui::Vec2,
     dragging: Option<usize>,
     drag_offset: egui::Vec2,
     selected: Option<usize>,
+    pin_on_drag: bool, // ドラッグしたノードをその位置に固定する
+    search_query: String,
+    search_missed: bool,
+    ego_mode: bool, // 選択ノードの近傍のみ表示
+    ego_depth: usize,
+    avoid_label_overlap: bool,
+    label_min_freq: usize, // これ未満の頻度のノードはラベルを表示しない
+    hovered_edge: Option<usize>,
 }
 
 impl Default for NetworkView {
     fn default() -> Self {
         Self {
             network: None,
-            positions: Vec::new(),
-            velocities: Vec::new(),
-            pos_nouns: true,
-            pos_verbs: true,
-            pos_adjectives: true,
-            pos_adverbs: false,
+            network_config: CooccurrenceConfig::default(),
+            layout: ForceLayout::default(),
+            layout_method: LayoutMethod::Force,
+            layout_seed: 1,
+            layout_task: None,
+            pos_filter: CooccurrenceConfig::default().pos_filter,
             min_word_freq: 2,
             min_edge_count: 2,
             max_nodes: 60,
             stopwords_input: String::new(),
+            edge_measure: EdgeMeasure::Count,
+            min_edge_weight: 0.0,
+            max_edges: 0,
+            unit: CooccurrenceUnit::Sentence,
+            window_size: 5,
+            community_method: CommunityMethod::Louvain,
+            scope_unit: None,
+            scope_label: String::new(),
+            color_mode: NodeColorMode::Community,
+            size_metric: NodeMetric::Frequency,
+            show_table: true,
+            sort_key: NodeSortKey::Metric(NodeMetric::Frequency),
+            sort_descending: true,
+            export_format: NetworkFormat::GraphMl,
+            image_width: 1600,
+            image_height: 1200,
+            embed_font: true,
+            canvas_size: egui::vec2(800.0, CANVAS_HEIGHT),
             simulating: true,
             zoom: 1.0,
             pan: egui::Vec2::ZERO,
             dragging: None,
             drag_offset: egui::Vec2::ZERO,
             selected: None,
+            pin_on_drag: true,
+            search_query: String::new(),
+            search_missed: false,
+            ego_mode: false,
+            ego_depth: 1,
+            avoid_label_overlap: true,
+            label_min_freq: 1,
+            hovered_edge: None,
         }
     }
 }
 
 impl NetworkView {
     fn build_config(&self) -> CooccurrenceConfig {
-        let mut target_pos = Vec::new();
-        if self.pos_nouns {
-            target_pos.push("名詞".to_string());
-        }
-        if self.pos_verbs {
-            target_pos.push("動詞".to_string());
-        }
-        if self.pos_adjectives {
-            target_pos.push("形容詞".to_string());
-        }
-        if self.pos_adverbs {
-            target_pos.push("副詞".to_string());
-        }
-
         let stopwords: HashSet<String> = self
             .stopwords_input
             .split(|c: char| c == ',' || c == '、' || c.is_whitespace())
@@ -256,34 +467,32 @@ impl NetworkView {
             .collect();
 
         CooccurrenceConfig {
-            target_pos,
+            pos_filter: self.pos_filter.clone(),
             min_word_freq: self.min_word_freq.max(1),
             min_edge_count: self.min_edge_count.max(1),
             max_nodes: self.max_nodes.max(1),
             stopwords,
+            edge_measure: self.edge_measure,
+            min_edge_weight: self.min_edge_weight,
+            max_edges: self.max_edges,
+            unit: self.unit,
+            community_method: self.community_method,
+            scope: self.scope_unit.map(|unit| UnitScope {
+                unit,
+                label: self.scope_label.clone(),
+            }),
         }
     }
 
     fn rebuild(&mut self, core: &TokenizerCore) {
         let config = self.build_config();
-        let network = core.compute_cooccurrence(&config);
-
-        // 円周上に初期配置
-        let n = network.nodes.len();
-        self.positions = (0..n)
-            .map(|i| {
-                if n == 0 {
-                    egui::Vec2::ZERO
-                } else {
-                    let theta = i as f32 / n as f32 * std::f32::consts::TAU;
-                    let r = 200.0;
-                    egui::vec2(r * theta.cos(), r * theta.sin())
-                }
-            })
-            .collect();
-        self.velocities = vec![egui::Vec2::ZERO; n];
-        self.network = Some(network);
-        self.simulating = true;
+        self.network = Some(core.compute_cooccurrence(&config));
+        self.network_config = config;
+        self.apply_layout();
+        self.reset_view();
+    }
+
+    fn reset_view(&mut self) {
         self.dragging = None;
         self.drag_offset = egui::Vec2::ZERO;
         self.selected = None;
@@ -291,15 +500,171 @@ impl NetworkView {
         self.zoom = 1.0;
     }
 
+    /// 保存されていた計算条件を設定 UI に反映する
+    fn apply_config(&mut self, config: &CooccurrenceConfig) {
+        self.pos_filter = config.pos_filter.clone();
+        self.min_word_freq = config.min_word_freq;
+        self.min_edge_count = config.min_edge_count;
+        self.max_nodes = config.max_nodes;
+        let mut stopwords: Vec<&str> = config.stopwords.iter().map(|s| s.as_str()).collect();
+        stopwords.sort();
+        self.stopwords_input = stopwords.join(", ");
+        self.edge_measure = config.edge_measure;
+        self.min_edge_weight = config.min_edge_weight;
+        self.max_edges = config.max_edges;
+        self.unit = config.unit;
+        if let CooccurrenceUnit::Window(size) = config.unit {
+            self.window_size = size;
+        }
+        self.community_method = config.community_method;
+        self.scope_unit = config.scope.as_ref().map(|scope| scope.unit);
+        self.scope_label = config
+            .scope
+            .as_ref()
+            .map(|scope| scope.label.clone())
+            .unwrap_or_default();
+    }
+
+    /// 現在のネットワーク・計算条件・配置をファイルに保存する
+    fn save_network(&self, path: std::path::PathBuf) -> std::io::Result<()> {
+        match self.saved_network() {
+            Some(saved) => saved.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// 現在のネットワーク・計算条件・配置 (ネットワークがなければ None)
+    fn saved_network(&self) -> Option<SavedNetwork> {
+        self.network.as_ref().map(|network| SavedNetwork {
+            config: self.network_config.clone(),
+            network: network.clone(),
+            positions: self.layout.positions.clone(),
+            pinned: self.layout.pinned.clone(),
+        })
+    }
+
+    /// 保存したネットワークを読み込む
+    ///
+    /// テキストが読み込まれていれば保存時の条件で計算し直し、配置は単語で対応付ける。
+    /// テキストがなければ保存されたネットワークをそのまま復元する。
+    fn load_network(
+        &mut self,
+        path: std::path::PathBuf,
+        core: &TokenizerCore,
+    ) -> std::io::Result<()> {
+        self.restore_network(SavedNetwork::load(path)?, core);
+        Ok(())
+    }
+
+    /// 保存されていたネットワークと配置を復元する (テキストが解析済みなら計算し直す)
+    fn restore_network(&mut self, saved: SavedNetwork, core: &TokenizerCore) {
+        self.apply_config(&saved.config);
+        let (network, positions, pinned) = if core.tokens.is_empty() {
+            (
+                saved.network.clone(),
+                saved.positions.clone(),
+                saved.pinned.clone(),
+            )
+        } else {
+            let network = core.compute_cooccurrence(&saved.config);
+            let matched = saved.layout_for(&network);
+            (network, matched.positions, matched.pinned)
+        };
+        let mut layout = ForceLayout::new(positions, self.layout.config.clone());
+        layout.pinned = pinned;
+        self.layout = layout;
+        self.layout_task = None;
+        self.network = Some(network);
+        self.network_config = saved.config;
+        self.layout_method = LayoutMethod::Force;
+        self.simulating = false;
+        self.reset_view();
+    }
+
+    /// プロジェクトに保存する状態 (設定 UI の条件・計算済みのネットワークと配置)
+    fn settings(&self) -> NetworkSettings {
+        NetworkSettings {
+            config: self.build_config(),
+            layout_method: self.layout_method,
+            layout_seed: self.layout_seed,
+            saved: self.saved_network(),
+        }
+    }
+
+    /// プロジェクトに保存されていた状態を復元する
+    fn restore_settings(&mut self, settings: NetworkSettings, core: &TokenizerCore) {
+        match settings.saved {
+            Some(saved) => self.restore_network(saved, core),
+            None => {
+                self.network = None;
+                self.reset_view();
+            }
+        }
+        self.apply_config(&settings.config);
+        self.layout_method = settings.layout_method;
+        self.layout_seed = settings.layout_seed;
+    }
+
+    /// 選択中の配置方法で配置し直す (力指向の場合は円周上から動かし始める)
+    fn apply_layout(&mut self) {
+        let Some(network) = &self.network else {
+            return;
+        };
+        // 計算に時間のかかる配置は別スレッドで求め、終わるまでは円周上に並べておく
+        let method = self.layout_method;
+        self.layout_task = None;
+        let positions = match method {
+            LayoutMethod::Force | LayoutMethod::CircularByCommunity => {
+                compute_layout(network, method, self.layout_seed, LAYOUT_RADIUS)
+            }
+            _ => {
+                self.layout_task = Some(LayoutTask::spawn(
+                    network.clone(),
+                    method,
+                    self.layout_seed,
+                    LAYOUT_RADIUS,
+                ));
+                ForceLayout::circle(network.nodes.len(), LAYOUT_RADIUS)
+            }
+        };
+        self.layout = ForceLayout::new(positions, self.layout.config.clone());
+        self.simulating = method == LayoutMethod::Force;
+        self.dragging = None;
+    }
+
+    /// 別スレッドで計算中の配置の進捗を表示し、終わっていれば反映する
+    fn layout_task_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(task) = &self.layout_task else {
+            return;
+        };
+        match task.try_take() {
+            Ok(None) => {
+                let progress = task.progress();
+                ui.add(egui::ProgressBar::new(progress).text(format!(
+                    "{} の配置を計算中 ({:.0}%)",
+                    self.layout_method.label(),
+                    progress * 100.0
+                )));
+            }
+            Ok(Some(positions)) => {
+                self.layout = ForceLayout::new(positions, self.layout.config.clone());
+                self.layout_task = None;
+                self.dragging = None;
+            }
+            Err(e) => {
+                eprintln!("配置の計算エラー: {}", e);
+                self.layout_task = None;
+            }
+        }
+    }
+
     fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
         // 設定 UI
-        ui.horizontal_wrapped(|ui| {
-            ui.label("対象品詞:");
-            ui.checkbox(&mut self.pos_nouns, "名詞");
-            ui.checkbox(&mut self.pos_verbs, "動詞");
-            ui.checkbox(&mut self.pos_adjectives, "形容詞");
-            ui.checkbox(&mut self.pos_adverbs, "副詞");
-        });
+        egui::CollapsingHeader::new("対象品詞")
+            .id_source("network_pos_filter")
+            .show(ui, |ui| {
+                pos_filter_ui(ui, "network_pos_tree", &mut self.pos_filter);
+            });
         ui.horizontal_wrapped(|ui| {
             ui.label("最小単語頻度:");
             ui.add(
@@ -320,15 +685,183 @@ impl NetworkView {
                     .clamp_range(2..=500),
             );
         });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("共起の単位:");
+            egui::ComboBox::from_id_source("network_unit")
+                .selected_text(self.unit.label())
+                .show_ui(ui, |ui| {
+                    for unit in [
+                        CooccurrenceUnit::Sentence,
+                        CooccurrenceUnit::Paragraph,
+                        CooccurrenceUnit::Document,
+                        CooccurrenceUnit::Window(self.window_size),
+                    ] {
+                        ui.selectable_value(&mut self.unit, unit, unit.label());
+                    }
+                });
+            if matches!(self.unit, CooccurrenceUnit::Window(_)) {
+                ui.label("窓の語数:");
+                ui.add(
+                    egui::DragValue::new(&mut self.window_size)
+                        .speed(1.0)
+                        .clamp_range(2..=100),
+                );
+                self.unit = CooccurrenceUnit::Window(self.window_size);
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("対象範囲:");
+            egui::ComboBox::from_id_source("network_scope_unit")
+                .selected_text(self.scope_unit.map(|u| u.label()).unwrap_or("テキスト全体"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.scope_unit, None, "テキスト全体");
+                    for unit in StructureUnit::ALL {
+                        ui.selectable_value(&mut self.scope_unit, Some(unit), unit.label());
+                    }
+                });
+            if let Some(unit) = self.scope_unit {
+                let units = core.structure.units(unit);
+                if !units.iter().any(|u| u.label == self.scope_label) {
+                    self.scope_label = units.first().map(|u| u.label.clone()).unwrap_or_default();
+                }
+                egui::ComboBox::from_id_source("network_scope_label")
+                    .selected_text(self.scope_label.as_str())
+                    .show_ui(ui, |ui| {
+                        for u in &units {
+                            ui.selectable_value(&mut self.scope_label, u.label.clone(), &u.label);
+                        }
+                    });
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("エッジの重み:");
+            egui::ComboBox::from_id_source("network_edge_measure")
+                .selected_text(self.edge_measure.label())
+                .show_ui(ui, |ui| {
+                    for measure in EdgeMeasure::ALL {
+                        ui.selectable_value(&mut self.edge_measure, measure, measure.label());
+                    }
+                });
+            ui.label("関連度の下限:");
+            ui.add(
+                egui::DragValue::new(&mut self.min_edge_weight)
+                    .speed(0.01)
+                    .clamp_range(-20.0..=1000.0),
+            );
+            ui.label("最大エッジ数 (0 で無制限):");
+            ui.add(
+                egui::DragValue::new(&mut self.max_edges)
+                    .speed(1.0)
+                    .clamp_range(0..=5000),
+            );
+        });
         ui.horizontal(|ui| {
-            ui.label("除外単語 (カンマ/空白区切り):");
+            ui.label("追加の除外単語 (カンマ/空白区切り、ストップワードに加えて除外):");
             ui.text_edit_singleline(&mut self.stopwords_input);
         });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("コミュニティ検出:");
+            let previous = self.community_method;
+            egui::ComboBox::from_id_source("network_community_method")
+                .selected_text(self.community_method.label())
+                .show_ui(ui, |ui| {
+                    for method in CommunityMethod::ALL {
+                        ui.selectable_value(&mut self.community_method, method, method.label());
+                    }
+                });
+            // 手法の変更はレイアウトを保ったまま再検出する
+            // (保存する計算条件もネットワークに合わせて更新する)
+            if self.community_method != previous {
+                if let Some(network) = &mut self.network {
+                    network.detect_communities(self.community_method);
+                    self.network_config.community_method = self.community_method;
+                }
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("ノードの大きさ:");
+            egui::ComboBox::from_id_source("network_size_metric")
+                .selected_text(self.size_metric.label())
+                .show_ui(ui, |ui| {
+                    for metric in NodeMetric::ALL {
+                        ui.selectable_value(&mut self.size_metric, metric, metric.label());
+                    }
+                });
+            ui.label("ノードの色:");
+            egui::ComboBox::from_id_source("network_color_mode")
+                .selected_text(self.color_mode.label())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut self.color_mode,
+                        NodeColorMode::Community,
+                        NodeColorMode::Community.label(),
+                    );
+                    for metric in NodeMetric::ALL {
+                        let mode = NodeColorMode::Metric(metric);
+                        ui.selectable_value(&mut self.color_mode, mode, mode.label());
+                    }
+                });
+            ui.checkbox(&mut self.show_table, "中心性の表を表示");
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("ラベル:");
+            ui.checkbox(&mut self.avoid_label_overlap, "重なりを避ける");
+            ui.label("表示する最小頻度:");
+            ui.add(
+                egui::DragValue::new(&mut self.label_min_freq)
+                    .speed(1.0)
+                    .clamp_range(1..=1000),
+            );
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("配置方法:");
+            let mut changed = false;
+            egui::ComboBox::from_id_source("network_layout_method")
+                .selected_text(self.layout_method.label())
+                .show_ui(ui, |ui| {
+                    for method in LayoutMethod::ALL {
+                        changed |= ui
+                            .selectable_value(&mut self.layout_method, method, method.label())
+                            .changed();
+                    }
+                });
+            if self.layout_method.uses_seed() {
+                ui.label("乱数シード:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut self.layout_seed).speed(1.0))
+                    .changed();
+            }
+            // 静的な配置は同じ条件なら常に同じ結果になる
+            if changed {
+                self.apply_layout();
+            }
+        });
 
         ui.horizontal(|ui| {
             if ui.button("ネットワークを計算").clicked() {
                 self.rebuild(core);
             }
+            if ui.button("ネットワークを読み込み").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("共起ネットワーク", &["json"])
+                    .pick_file()
+                {
+                    if let Err(e) = self.load_network(path, core) {
+                        eprintln!("ネットワーク読み込みエラー: {}", e);
+                    }
+                }
+            }
+            if self.network.is_some() && ui.button("ネットワークを保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("共起ネットワーク", &["json"])
+                    .set_file_name("network_layout.json")
+                    .save_file()
+                {
+                    if let Err(e) = self.save_network(path) {
+                        eprintln!("ネットワーク保存エラー: {}", e);
+                    }
+                }
+            }
             if self.network.is_some() {
                 let label = if self.simulating {
                     "レイアウト停止"
@@ -337,36 +870,400 @@ impl NetworkView {
                 };
                 if ui.button(label).clicked() {
                     self.simulating = !self.simulating;
+                    if self.simulating {
+                        self.layout_task = None;
+                        self.layout.reset_motion();
+                    }
                 }
                 if ui.button("配置リセット").clicked() {
-                    self.rebuild(core);
+                    self.apply_layout();
+                }
+                if ui.button("全体を表示").clicked() {
+                    self.fit_to_view();
+                }
+                ui.label("近似の粗さ θ:")
+                    .on_hover_text("Barnes-Hut 法のパラメータ。0 で厳密計算、大きいほど高速");
+                ui.add(
+                    egui::DragValue::new(&mut self.layout.config.theta)
+                        .speed(0.01)
+                        .clamp_range(0.0..=2.0),
+                );
+                if !self.simulating && self.layout.is_converged() {
+                    ui.label("(配置が収束しました)");
                 }
             }
         });
+        self.layout_task_ui(ui);
+
+        if self.network.is_some() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("単語を検索:");
+                let response = ui.text_edit_singleline(&mut self.search_query);
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("検索").clicked() || submitted {
+                    self.search_word();
+                }
+                if self.search_missed {
+                    ui.label("(見つかりません)");
+                }
+                ui.separator();
+                ui.checkbox(&mut self.ego_mode, "選択ノードの近傍のみ表示");
+                ui.label("深さ:");
+                ui.add(
+                    egui::DragValue::new(&mut self.ego_depth)
+                        .speed(0.1)
+                        .clamp_range(1..=10),
+                );
+            });
+            ui.horizontal_wrapped(|ui| {
+                ui.checkbox(&mut self.pin_on_drag, "ドラッグしたノードを固定");
+                if let Some(i) = self.selected {
+                    let word = self
+                        .network
+                        .as_ref()
+                        .map(|n| n.nodes[i].word.clone())
+                        .unwrap_or_default();
+                    ui.label(format!("選択中: {}", word));
+                    let pinned = self.layout.pinned[i];
+                    if ui.button(if pinned { "固定解除" } else { "固定" }).clicked() {
+                        self.layout.pinned[i] = !pinned;
+                        self.layout.stop_node(i);
+                    }
+                }
+                let pinned_count = self.layout.pinned.iter().filter(|&&p| p).count();
+                if pinned_count > 0 {
+                    ui.label(format!("固定: {} 件", pinned_count));
+                    if ui.button("すべて固定解除").clicked() {
+                        self.layout.pinned.iter_mut().for_each(|p| *p = false);
+                    }
+                }
+            });
+        }
 
         if let Some(network) = &self.network {
-            ui.label(format!(
-                "ノード: {} 件 / エッジ: {} 件",
-                network.nodes.len(),
-                network.edges.len()
-            ));
+            if network.units == 1 {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    "共起を数える単位が 1 つしかないため共起関係を求められません。\
+                     共起の単位を変えてください。",
+                );
+            }
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format!(
+                    "ノード: {} 件 / エッジ: {} 件 (重み: {}) / コミュニティ: {} 個 (Q = {:.3})",
+                    network.nodes.len(),
+                    network.edges.len(),
+                    network.edge_measure.label(),
+                    network.community_sizes().len(),
+                    network.modularity
+                ));
+                if ui.button("ノード一覧を CSV 保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("network_nodes.csv")
+                        .save_file()
+                    {
+                        if let Err(e) = network.save_nodes_to_csv(path) {
+                            eprintln!("CSV保存エラー: {}", e);
+                        }
+                    }
+                }
+                if ui.button("エッジ一覧を CSV 保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("network_edges.csv")
+                        .save_file()
+                    {
+                        if let Err(e) = network.save_edges_to_csv(path) {
+                            eprintln!("CSV保存エラー: {}", e);
+                        }
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("network_export_format")
+                    .selected_text(self.export_format.label())
+                    .show_ui(ui, |ui| {
+                        for format in NetworkFormat::ALL {
+                            ui.selectable_value(&mut self.export_format, format, format.label());
+                        }
+                    });
+                if ui.button("ネットワークを書き出し").clicked() {
+                    let format = self.export_format;
+                    let extension = format.extension();
+                    if let Some(path) = FileDialog::new()
+                        .add_filter(format.label(), &[extension])
+                        .set_file_name(&format!("network.{}", extension))
+                        .save_file()
+                    {
+                        // 現在の配置をそのまま書き出す
+                        let positions = Some(self.layout.positions.as_slice());
+                        if let Err(e) = network.save_as(format, positions, path) {
+                            eprintln!("ネットワーク保存エラー: {}", e);
+                        }
+                    }
+                }
+            });
         }
 
         if self.network.is_some() {
-            self.draw_canvas(ui);
+            ui.horizontal_wrapped(|ui| {
+                ui.label("画像サイズ (px):");
+                ui.add(
+                    egui::DragValue::new(&mut self.image_width)
+                        .speed(10.0)
+                        .clamp_range(200..=8000),
+                );
+                ui.label("×");
+                ui.add(
+                    egui::DragValue::new(&mut self.image_height)
+                        .speed(10.0)
+                        .clamp_range(200..=8000),
+                );
+                ui.checkbox(&mut self.embed_font, "SVG にフォントを埋め込む");
+                if ui.button("SVG で保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("SVG", &["svg"])
+                        .set_file_name("network.svg")
+                        .save_file()
+                    {
+                        let scene = self.export_scene();
+                        if let Err(e) = scene.save_svg(NOTO_SANS_JP, self.embed_font, path) {
+                            eprintln!("画像保存エラー: {}", e);
+                        }
+                    }
+                }
+                if ui.button("PNG で保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("PNG", &["png"])
+                        .set_file_name("network.png")
+                        .save_file()
+                    {
+                        if let Err(e) = self.export_scene().save_png(NOTO_SANS_JP, path) {
+                            eprintln!("画像保存エラー: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+
+        if self.network.is_some() {
+            if self.show_table {
+                ui.horizontal_top(|ui| {
+                    let table_width = (ui.available_width() * 0.4).min(420.0);
+                    let canvas_width = ui.available_width() - table_width - 8.0;
+                    ui.allocate_ui(egui::vec2(canvas_width, CANVAS_HEIGHT), |ui| {
+                        self.draw_canvas(ui);
+                    });
+                    ui.vertical(|ui| {
+                        ui.set_width(table_width);
+                        self.centrality_table(ui);
+                    });
+                });
+            } else {
+                self.draw_canvas(ui);
+            }
         } else {
             ui.label("「ネットワークを計算」ボタンを押すと共起ネットワークが表示されます。");
         }
     }
 
+    /// 選択中の指標に基づく各ノードの半径 (ワールド座標)
+    fn node_radii(&self) -> Vec<f32> {
+        let Some(network) = &self.network else {
+            return Vec::new();
+        };
+        if self.size_metric == NodeMetric::Frequency {
+            return network
+                .nodes
+                .iter()
+                .map(|n| node_radius(n.frequency))
+                .collect();
+        }
+        let max = network
+            .nodes
+            .iter()
+            .map(|n| n.metric(self.size_metric))
+            .fold(0.0, f64::max);
+        network
+            .nodes
+            .iter()
+            .map(|n| {
+                let t = if max > 0.0 {
+                    (n.metric(self.size_metric) / max) as f32
+                } else {
+                    0.0
+                };
+                6.0 + t.sqrt() * 18.0
+            })
+            .collect()
+    }
+
+    /// ノードの中心性の表 (見出しクリックで並べ替え、行クリックでノード選択)
+    fn centrality_table(&mut self, ui: &mut egui::Ui) {
+        let Some(network) = &self.network else {
+            return;
+        };
+
+        let mut order: Vec<usize> = (0..network.nodes.len()).collect();
+        let nodes = &network.nodes;
+        order.sort_by(|&a, &b| {
+            let ordering = match self.sort_key {
+                NodeSortKey::Word => nodes[a].word.cmp(&nodes[b].word),
+                NodeSortKey::Community => nodes[a].community.cmp(&nodes[b].community),
+                NodeSortKey::Metric(metric) => nodes[a]
+                    .metric(metric)
+                    .total_cmp(&nodes[b].metric(metric)),
+            };
+            if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let columns = [
+            ("単語", NodeSortKey::Word),
+            ("頻度", NodeSortKey::Metric(NodeMetric::Frequency)),
+            ("次数", NodeSortKey::Metric(NodeMetric::Degree)),
+            ("重み付き次数", NodeSortKey::Metric(NodeMetric::WeightedDegree)),
+            ("媒介", NodeSortKey::Metric(NodeMetric::Betweenness)),
+            ("近接", NodeSortKey::Metric(NodeMetric::Closeness)),
+            ("固有ベクトル", NodeSortKey::Metric(NodeMetric::Eigenvector)),
+            ("コミュニティ", NodeSortKey::Community),
+        ];
+
+        let mut clicked_key = None;
+        let mut clicked_row = None;
+        egui::ScrollArea::both()
+            .id_source("network_centrality_table")
+            .max_height(CANVAS_HEIGHT)
+            .show(ui, |ui| {
+                egui::Grid::new("network_centrality_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (title, key) in columns {
+                            let label = if self.sort_key == key {
+                                format!("{} {}", title, if self.sort_descending { "▼" } else { "▲" })
+                            } else {
+                                title.to_string()
+                            };
+                            if ui.selectable_label(self.sort_key == key, label).clicked() {
+                                clicked_key = Some(key);
+                            }
+                        }
+                        ui.end_row();
+
+                        for &i in &order {
+                            let node = &nodes[i];
+                            let c = &node.centrality;
+                            if ui
+                                .selectable_label(self.selected == Some(i), &node.word)
+                                .clicked()
+                            {
+                                clicked_row = Some(i);
+                            }
+                            ui.label(node.frequency.to_string());
+                            ui.label(c.degree.to_string());
+                            ui.label(format!("{:.3}", c.weighted_degree));
+                            ui.label(format!("{:.3}", c.betweenness));
+                            ui.label(format!("{:.3}", c.closeness));
+                            ui.label(format!("{:.3}", c.eigenvector));
+                            ui.label((node.community + 1).to_string());
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(key) = clicked_key {
+            if self.sort_key == key {
+                self.sort_descending = !self.sort_descending;
+            } else {
+                self.sort_key = key;
+                self.sort_descending = key != NodeSortKey::Word;
+            }
+        }
+        if let Some(i) = clicked_row {
+            self.selected = Some(i);
+        }
+    }
+
+    /// 検索語に一致するノードを選択して画面の中央に表示する
+    fn search_word(&mut self) {
+        let Some(network) = &self.network else {
+            return;
+        };
+        match network.find_node(&self.search_query) {
+            Some(i) => {
+                let (x, y) = self.layout.positions[i];
+                self.pan = -egui::vec2(x, y);
+                self.selected = Some(i);
+                self.search_missed = false;
+            }
+            None => self.search_missed = true,
+        }
+    }
+
+    /// 表示中のノードがすべて描画領域に収まるようにズーム・パンを合わせる
+    fn fit_to_view(&mut self) {
+        let visible = self.visible_nodes();
+        let radii = self.node_radii();
+        let mut bounds = egui::Rect::NOTHING;
+        for (i, &(x, y)) in self.layout.positions.iter().enumerate() {
+            if visible.as_ref().map(|v| v[i]).unwrap_or(true) {
+                bounds = bounds.union(egui::Rect::from_center_size(
+                    egui::pos2(x, y),
+                    egui::Vec2::splat(radii[i] * 2.0),
+                ));
+            }
+        }
+        if !bounds.is_positive() {
+            return;
+        }
+        // ラベルの分の余白を画面上で確保する
+        let available =
+            (self.canvas_size - egui::Vec2::splat(FIT_MARGIN * 2.0)).max(egui::vec2(1.0, 1.0));
+        self.zoom = (available.x / bounds.width())
+            .min(available.y / bounds.height())
+            .clamp(0.2, 5.0);
+        self.pan = -bounds.center().to_vec2();
+    }
+
+    /// 表示するノード (エゴネットワーク表示中のみ Some)
+    fn visible_nodes(&self) -> Option<Vec<bool>> {
+        let network = self.network.as_ref()?;
+        let center = self.selected.filter(|_| self.ego_mode)?;
+        Some(network.ego_nodes(center, self.ego_depth))
+    }
+
+    /// ワールド座標 `target` にある表示中のノード
+    fn hit_node(
+        &self,
+        target: egui::Vec2,
+        radii: &[f32],
+        visible: Option<&[bool]>,
+    ) -> Option<usize> {
+        self.layout
+            .positions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| visible.map(|v| v[*i]).unwrap_or(true))
+            // ワールド距離 r、加えて画面 4px 分の余白
+            .find(|(i, &(x, y))| {
+                (egui::vec2(x, y) - target).length() <= radii[*i] + 4.0 / self.zoom
+            })
+            .map(|(i, _)| i)
+    }
+
     fn draw_canvas(&mut self, ui: &mut egui::Ui) {
-        let desired_size = egui::vec2(ui.available_width(), 480.0);
+        let desired_size = egui::vec2(ui.available_width(), CANVAS_HEIGHT);
         let (response, painter) = ui.allocate_painter(desired_size, egui::Sense::click_and_drag());
         let rect = response.rect;
         let center = rect.center();
 
         // 背景
-        painter.rect_filled(rect, 4.0, egui::Color32::from_rgb(20, 22, 28));
+        painter.rect_filled(rect, 4.0, CANVAS_BACKGROUND);
         painter.rect_stroke(
             rect,
             4.0,
@@ -385,13 +1282,13 @@ impl NetworkView {
         // ローカル変数にスナップショットしてクロージャで使用 (ボローチェッカー対策)
         let mut pan = self.pan;
         let zoom = self.zoom;
-        let to_screen = |w: egui::Vec2, pan: egui::Vec2| -> egui::Pos2 {
-            center + (w + pan) * zoom
-        };
         let to_world = |s: egui::Pos2, pan: egui::Vec2| -> egui::Vec2 {
             (s - center) / zoom - pan
         };
 
+        let radii = self.node_radii();
+        let visible = self.visible_nodes();
+
         // ヒットテスト + ドラッグ処理
         let pointer_screen = response.interact_pointer_pos();
         let hover_screen = response.hover_pos();
@@ -399,21 +1296,14 @@ impl NetworkView {
         if response.drag_started() {
             if let Some(p) = pointer_screen {
                 let target = to_world(p, pan);
-                let mut hit: Option<usize> = None;
-                if let Some(network) = &self.network {
-                    for (i, pos) in self.positions.iter().enumerate() {
-                        let r = node_radius(network.nodes[i].frequency);
-                        // ワールド距離 r、加えて画面 4px 分の余白
-                        if (*pos - target).length() <= r + 4.0 / zoom {
-                            hit = Some(i);
-                            break;
-                        }
-                    }
-                }
+                let hit = self.hit_node(target, &radii, visible.as_deref());
                 self.dragging = hit;
                 // 押下位置とノード中心のオフセットを保持しテレポートを防ぐ
                 self.drag_offset = match hit {
-                    Some(idx) => self.positions[idx] - target,
+                    Some(idx) => {
+                        let (x, y) = self.layout.positions[idx];
+                        egui::vec2(x, y) - target
+                    }
                     None => egui::Vec2::ZERO,
                 };
                 self.selected = hit.or(self.selected);
@@ -422,10 +1312,9 @@ impl NetworkView {
 
         if response.dragged() {
             if let (Some(idx), Some(p)) = (self.dragging, pointer_screen) {
-                self.positions[idx] = to_world(p, pan) + self.drag_offset;
-                if idx < self.velocities.len() {
-                    self.velocities[idx] = egui::Vec2::ZERO;
-                }
+                let w = to_world(p, pan) + self.drag_offset;
+                self.layout.positions[idx] = (w.x, w.y);
+                self.layout.stop_node(idx);
             } else {
                 // 空白部分のドラッグ → パン
                 let delta = response.drag_delta();
@@ -434,6 +1323,9 @@ impl NetworkView {
         }
 
         if response.drag_released() {
+            if let Some(idx) = self.dragging.filter(|_| self.pin_on_drag) {
+                self.layout.pinned[idx] = true;
+            }
             self.dragging = None;
             self.drag_offset = egui::Vec2::ZERO;
         }
@@ -443,160 +1335,370 @@ impl NetworkView {
             // interact_pointer_pos は press 位置を返すので click 判定にも妥当
             if let Some(p) = pointer_screen.or(hover_screen) {
                 let target = to_world(p, pan);
-                let mut hit: Option<usize> = None;
-                if let Some(network) = &self.network {
-                    for (i, pos) in self.positions.iter().enumerate() {
-                        let r = node_radius(network.nodes[i].frequency);
-                        if (*pos - target).length() <= r + 4.0 / zoom {
-                            hit = Some(i);
-                            break;
-                        }
-                    }
+                let hit = self.hit_node(target, &radii, visible.as_deref());
+                // 近傍表示中は空白のクリックで選択を解除しない
+                if hit.is_some() || !self.ego_mode {
+                    self.selected = hit;
+                }
+            }
+        }
+
+        // ダブルクリックで固定を切り替える
+        if response.double_clicked() {
+            if let Some(p) = pointer_screen.or(hover_screen) {
+                let target = to_world(p, pan);
+                if let Some(i) = self.hit_node(target, &radii, visible.as_deref()) {
+                    self.layout.pinned[i] = !self.layout.pinned[i];
+                    self.layout.stop_node(i);
                 }
-                self.selected = hit;
             }
         }
 
         // 更新したパンを書き戻す
         self.pan = pan;
 
-        // 物理シミュレーション 1 ステップ
+        // 物理シミュレーション 1 ステップ (収束したら自動停止)
         if self.simulating {
-            self.step_layout(rect.size());
+            if let Some(network) = &self.network {
+                let area = (rect.width(), rect.height());
+                if self.layout.step(network, area, self.dragging) && self.dragging.is_none() {
+                    self.simulating = false;
+                }
+            }
         }
 
-        // 描画: エッジ
-        if let Some(network) = &self.network {
-            let max_edge = network.edges.iter().map(|e| e.count).max().unwrap_or(1) as f32;
-            let highlight = self.selected;
-
-            // 弱いエッジを先に描画して強いエッジが上に重なるようにする
-            for edge in network.edges.iter().rev() {
-                let a = to_screen(self.positions[edge.source], pan);
-                let b = to_screen(self.positions[edge.target], pan);
-                let weight = (edge.count as f32) / max_edge;
-                let width = (0.6 + weight * 3.5).min(5.0);
-                let is_highlight = match highlight {
-                    Some(i) => edge.source == i || edge.target == i,
-                    None => false,
-                };
-                let color = if is_highlight {
-                    egui::Color32::from_rgb(240, 180, 80)
-                } else {
-                    let v = (60.0 + weight * 120.0) as u8;
-                    egui::Color32::from_rgba_unmultiplied(v, v, v.saturating_add(20), 200)
-                };
-                painter.line_segment([a, b], egui::Stroke::new(width, color));
+        // ホバー中のエッジ (ノードの上やドラッグ中は除く)
+        let to_screen = |(x, y): Point| center + (egui::vec2(x, y) + self.pan) * self.zoom;
+        self.hovered_edge = None;
+        if let (Some(network), Some(p), None) = (&self.network, hover_screen, self.dragging) {
+            let is_visible = |i: usize| visible.as_ref().map(|v| v[i]).unwrap_or(true);
+            if self.hit_node(to_world(p, self.pan), &radii, visible.as_deref()).is_none() {
+                let mut nearest = EDGE_HOVER_DISTANCE;
+                for (i, edge) in network.edges.iter().enumerate() {
+                    if !is_visible(edge.source) || !is_visible(edge.target) {
+                        continue;
+                    }
+                    let a = to_screen(self.layout.positions[edge.source]);
+                    let b = to_screen(self.layout.positions[edge.target]);
+                    let distance = distance_to_segment(p, a, b);
+                    if distance <= nearest {
+                        nearest = distance;
+                        self.hovered_edge = Some(i);
+                    }
+                }
             }
+        }
 
-            // 描画: ノード
-            let max_freq = network
-                .nodes
-                .iter()
-                .map(|n| n.frequency)
-                .max()
-                .unwrap_or(1) as f32;
-            for (i, node) in network.nodes.iter().enumerate() {
-                let p = to_screen(self.positions[i], pan);
-                let r = node_radius(node.frequency) * zoom;
-                let intensity = (node.frequency as f32 / max_freq).sqrt();
-                let fill = lerp_color(
-                    egui::Color32::from_rgb(80, 130, 220),
-                    egui::Color32::from_rgb(230, 90, 130),
-                    intensity,
-                );
-                let stroke = if Some(i) == highlight {
-                    egui::Stroke::new(2.5, egui::Color32::from_rgb(255, 220, 120))
-                } else {
-                    egui::Stroke::new(1.0, egui::Color32::from_rgb(20, 20, 30))
-                };
-                painter.circle(p, r, fill, stroke);
+        // 描画: エッジ・ノード・ラベル・コミュニティの凡例
+        self.canvas_size = rect.size();
+        let scene = self.build_scene(rect.size(), 1.0);
+        paint_scene(&painter, rect.min, &scene);
 
-                // ラベル
-                let font_size = (10.0 + intensity * 6.0).min(20.0);
-                painter.text(
-                    p + egui::vec2(0.0, -r - 2.0),
-                    egui::Align2::CENTER_BOTTOM,
-                    &node.word,
-                    egui::FontId::proportional(font_size),
-                    egui::Color32::from_rgb(235, 235, 240),
-                );
-            }
+        // ホバー中のエッジを強調し、共起回数と関連度を表示する
+        if let (Some(network), Some(i)) = (&self.network, self.hovered_edge) {
+            let edge = &network.edges[i];
+            let a = to_screen(self.layout.positions[edge.source]);
+            let b = to_screen(self.layout.positions[edge.target]);
+            painter.line_segment(
+                [a, b],
+                egui::Stroke::new(3.0, egui::Color32::from_rgb(120, 200, 255)),
+            );
+            let text = if network.edge_measure == EdgeMeasure::Count {
+                format!(
+                    "{} – {}\n共起 {} 回",
+                    network.nodes[edge.source].word,
+                    network.nodes[edge.target].word,
+                    edge.count
+                )
+            } else {
+                format!(
+                    "{} – {}\n共起 {} 回 / {} {:.3}",
+                    network.nodes[edge.source].word,
+                    network.nodes[edge.target].word,
+                    edge.count,
+                    network.edge_measure.label(),
+                    edge.weight
+                )
+            };
+            let galley = painter.layout_no_wrap(
+                text,
+                egui::FontId::proportional(12.0),
+                egui::Color32::from_rgb(235, 235, 240),
+            );
+            let label_rect = egui::Rect::from_center_size(
+                a + (b - a) / 2.0,
+                galley.size() + egui::vec2(10.0, 6.0),
+            );
+            painter.rect_filled(
+                label_rect,
+                3.0,
+                egui::Color32::from_rgba_unmultiplied(10, 10, 16, 220),
+            );
+            painter.galley(label_rect.min + egui::vec2(5.0, 3.0), galley);
         }
 
         // 凡例
         painter.text(
             rect.left_top() + egui::vec2(8.0, 6.0),
             egui::Align2::LEFT_TOP,
-            "ドラッグ: ノード移動 / 空白ドラッグ: パン / ホイール: ズーム",
+            "ドラッグ: ノード移動 / ダブルクリック: 固定の切り替え / 空白ドラッグ: パン / ホイール: ズーム",
             egui::FontId::proportional(11.0),
             egui::Color32::from_rgb(160, 160, 170),
         );
     }
 
-    fn step_layout(&mut self, area: egui::Vec2) {
+    /// 現在の配置・ズーム・選択状態から描画内容を作る
+    ///
+    /// `size` は出力先の大きさ、`scale` は画面表示に対する拡大率 (線の太さ・文字も拡大する)。
+    fn build_scene(&self, size: egui::Vec2, scale: f32) -> NetworkScene {
+        let mut scene = NetworkScene::new(size.x, size.y, to_rgba(CANVAS_BACKGROUND));
         let Some(network) = &self.network else {
-            return;
+            return scene;
         };
-        let n = network.nodes.len();
-        if n < 2 {
-            return;
+        let center = size / 2.0;
+        let to_scene = |(x, y): Point| -> (f32, f32) {
+            let p = center + (egui::vec2(x, y) + self.pan) * self.zoom * scale;
+            (p.x, p.y)
+        };
+        let radii = self.node_radii();
+        let weight_range = network.weight_range();
+        let highlight = self.selected;
+        let visible = self.visible_nodes();
+        let is_visible = |i: usize| visible.as_ref().map(|v| v[i]).unwrap_or(true);
+
+        // 弱いエッジを先に描画して強いエッジが上に重なるようにする
+        for edge in network.edges.iter().rev() {
+            if !is_visible(edge.source) || !is_visible(edge.target) {
+                continue;
+            }
+            let weight = network.relative_weight(edge.weight, weight_range);
+            let width = (0.6 + weight * 3.5).min(5.0);
+            let is_highlight = match highlight {
+                Some(i) => edge.source == i || edge.target == i,
+                None => false,
+            };
+            let color = if is_highlight {
+                [240, 180, 80, 255]
+            } else {
+                let v = (60.0 + weight * 120.0) as u8;
+                [v, v, v.saturating_add(20), 200]
+            };
+            scene.shapes.push(SceneShape::Line {
+                from: to_scene(self.layout.positions[edge.source]),
+                to: to_scene(self.layout.positions[edge.target]),
+                width: width * scale,
+                color,
+            });
         }
 
-        // 最適距離 k
-        let k = (area.x * area.y / n as f32).sqrt() * 0.35;
-        let dt = 0.6;
+        // ノード
+        let max_metric = |metric: NodeMetric| {
+            network
+                .nodes
+                .iter()
+                .map(|n| n.metric(metric))
+                .fold(0.0, f64::max)
+        };
+        let relative = |value: f64, max: f64| {
+            if max > 0.0 {
+                (value / max).sqrt() as f32
+            } else {
+                0.0
+            }
+        };
+        let max_size = max_metric(self.size_metric);
+        let max_color = match self.color_mode {
+            NodeColorMode::Metric(metric) => max_metric(metric),
+            NodeColorMode::Community => 0.0,
+        };
+        let community_sizes = network.community_sizes();
+        let mut node_rects: Vec<Option<egui::Rect>> = vec![None; network.nodes.len()];
+        // ラベルの候補: ノード・中心・半径・文字サイズ・優先度
+        let mut labels: Vec<(usize, Point, f32, f32, f32)> = Vec::new();
+        for (i, node) in network.nodes.iter().enumerate() {
+            if !is_visible(i) {
+                continue;
+            }
+            let p = to_scene(self.layout.positions[i]);
+            let r = radii[i] * self.zoom * scale;
+            let intensity = relative(node.metric(self.size_metric), max_size);
+            let fill = match self.color_mode {
+                NodeColorMode::Metric(metric) => lerp_color(
+                    egui::Color32::from_rgb(80, 130, 220),
+                    egui::Color32::from_rgb(230, 90, 130),
+                    relative(node.metric(metric), max_color),
+                ),
+                NodeColorMode::Community => {
+                    community_color(node.community, community_sizes[node.community])
+                }
+            };
+            let (stroke_width, stroke) = if Some(i) == highlight {
+                (2.5, [255, 220, 120, 255])
+            } else if self.layout.pinned[i] {
+                (2.0, [120, 190, 255, 255])
+            } else {
+                (1.0, [20, 20, 30, 255])
+            };
+            scene.shapes.push(SceneShape::Circle {
+                center: p,
+                radius: r,
+                fill: to_rgba(fill),
+                stroke_width: stroke_width * scale,
+                stroke,
+            });
 
-        let mut forces = vec![egui::Vec2::ZERO; n];
+            node_rects[i] = Some(egui::Rect::from_center_size(
+                egui::pos2(p.0, p.1),
+                egui::Vec2::splat(r * 2.0),
+            ));
 
-        // 反発力 O(n^2)
-        for i in 0..n {
-            for j in (i + 1)..n {
-                let mut delta = self.positions[i] - self.positions[j];
-                let mut dist = delta.length();
-                if dist < 0.01 {
-                    delta = egui::vec2(((i as f32) * 0.3).cos(), ((i as f32) * 0.7).sin());
-                    dist = 0.01;
-                }
-                let f = (k * k) / dist;
-                let dir = delta / dist;
-                forces[i] += dir * f;
-                forces[j] -= dir * f;
+            // ラベルの候補 (選択中のノードは頻度によらず表示する)
+            if node.frequency >= self.label_min_freq || Some(i) == highlight {
+                let font_size = (10.0 + intensity * 6.0).min(20.0);
+                labels.push((i, p, r, font_size * scale, intensity));
             }
         }
 
-        // 引力 (エッジに沿って)
-        let max_count = network.edges.iter().map(|e| e.count).max().unwrap_or(1) as f32;
-        for edge in &network.edges {
-            let delta = self.positions[edge.target] - self.positions[edge.source];
-            let dist = delta.length().max(0.01);
-            let weight = 0.5 + 0.5 * (edge.count as f32 / max_count);
-            let f = (dist * dist) / k * weight;
-            let dir = delta / dist;
-            forces[edge.source] += dir * f;
-            forces[edge.target] -= dir * f;
-        }
-
-        // 中心への弱い引力
-        for i in 0..n {
-            forces[i] += -self.positions[i] * 0.01;
+        // ラベル: 選択中・重要なノードから順に、他のラベルやノードと重ならない位置に置く
+        labels.sort_by(|a, b| {
+            (Some(b.0) == highlight)
+                .cmp(&(Some(a.0) == highlight))
+                .then(b.4.total_cmp(&a.4))
+        });
+        let mut placed: Vec<egui::Rect> = Vec::new();
+        for (i, p, r, font_size, _) in labels {
+            let word = &network.nodes[i].word;
+            let candidates = label_candidates(p, r + 2.0 * scale, label_extent(word, font_size));
+            let choice = if self.avoid_label_overlap {
+                let is_free = |rect: egui::Rect, avoid_nodes: bool| {
+                    let rect = rect.shrink(0.5);
+                    let overlaps_label = placed.iter().any(|other| other.intersects(rect));
+                    let overlaps_node = avoid_nodes
+                        && node_rects.iter().enumerate().any(|(j, other)| {
+                            j != i && other.map(|o| o.intersects(rect)).unwrap_or(false)
+                        });
+                    !overlaps_label && !overlaps_node
+                };
+                candidates
+                    .iter()
+                    .find(|c| is_free(c.2, true))
+                    .or_else(|| candidates.iter().find(|c| is_free(c.2, false)))
+                    .or_else(|| (Some(i) == highlight).then_some(&candidates[0]))
+                    .copied()
+            } else {
+                Some(candidates[0])
+            };
+            let Some((position, anchor, rect)) = choice else {
+                continue;
+            };
+            placed.push(rect);
+            scene.shapes.push(SceneShape::Text {
+                position,
+                text: word.clone(),
+                size: font_size,
+                color: [235, 235, 240, 255],
+                anchor,
+            });
         }
 
-        // 速度・位置更新 (温度減衰付き)
-        let max_speed = 60.0;
-        for i in 0..n {
-            if Some(i) == self.dragging {
-                self.velocities[i] = egui::Vec2::ZERO;
-                continue;
+        // コミュニティの凡例 (単独ノードのコミュニティは除く)
+        if self.color_mode == NodeColorMode::Community {
+            let shown: Vec<(usize, usize)> = community_sizes
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, size)| *size > 1)
+                .collect();
+            let mut y = size.y - (10.0 + 16.0 * shown.len().min(LEGEND_MAX) as f32) * scale;
+            for &(id, size) in shown.iter().take(LEGEND_MAX) {
+                let p = (16.0 * scale, y + 8.0 * scale);
+                scene.shapes.push(SceneShape::Circle {
+                    center: p,
+                    radius: 5.0 * scale,
+                    fill: to_rgba(community_color(id, size)),
+                    stroke_width: 0.0,
+                    stroke: [0, 0, 0, 0],
+                });
+                scene.shapes.push(SceneShape::Text {
+                    position: (p.0 + 10.0 * scale, p.1),
+                    text: format!("{:02} ({} 語)", id + 1, size),
+                    size: 11.0 * scale,
+                    color: [200, 200, 210, 255],
+                    anchor: TextAnchor::LeftCenter,
+                });
+                y += 16.0 * scale;
             }
-            self.velocities[i] = (self.velocities[i] + forces[i] * dt) * 0.82;
-            let speed = self.velocities[i].length();
-            if speed > max_speed {
-                self.velocities[i] *= max_speed / speed;
+            if shown.len() > LEGEND_MAX {
+                scene.shapes.push(SceneShape::Text {
+                    position: (26.0 * scale, y + 8.0 * scale),
+                    text: format!("… 他 {} 個", shown.len() - LEGEND_MAX),
+                    size: 11.0 * scale,
+                    color: [160, 160, 170, 255],
+                    anchor: TextAnchor::LeftCenter,
+                });
             }
-            self.positions[i] += self.velocities[i] * dt;
         }
+        scene
+    }
+
+    /// 画像書き出し用の描画内容 (画面に見えている範囲を指定サイズに拡大する)
+    fn export_scene(&self) -> NetworkScene {
+        let size = egui::vec2(self.image_width as f32, self.image_height as f32);
+        let scale = (size.x / self.canvas_size.x.max(1.0)).min(size.y / self.canvas_size.y.max(1.0));
+        self.build_scene(size, scale)
+    }
+}
+
+/// ラベルの大きさの目安 (全角 1 文字が文字サイズ四方、半角はその約半分の幅)
+fn label_extent(text: &str, size: f32) -> egui::Vec2 {
+    let width: f32 = text
+        .chars()
+        .map(|c| if c.is_ascii() { 0.55 } else { 1.0 })
+        .sum();
+    egui::vec2(width * size, size * 1.1)
+}
+
+/// ノードの周囲のラベル位置の候補 (上・下・右・左の順)
+fn label_candidates(
+    center: Point,
+    offset: f32,
+    extent: egui::Vec2,
+) -> [(Point, TextAnchor, egui::Rect); 4] {
+    let (x, y) = center;
+    let (w, h) = (extent.x, extent.y);
+    [
+        (
+            (x, y - offset),
+            TextAnchor::CenterBottom,
+            egui::Rect::from_min_size(egui::pos2(x - w / 2.0, y - offset - h), extent),
+        ),
+        (
+            (x, y + offset),
+            TextAnchor::CenterTop,
+            egui::Rect::from_min_size(egui::pos2(x - w / 2.0, y + offset), extent),
+        ),
+        (
+            (x + offset, y),
+            TextAnchor::LeftCenter,
+            egui::Rect::from_min_size(egui::pos2(x + offset, y - h / 2.0), extent),
+        ),
+        (
+            (x - offset, y),
+            TextAnchor::RightCenter,
+            egui::Rect::from_min_size(egui::pos2(x - offset - w, y - h / 2.0), extent),
+        ),
+    ]
+}
+
+/// 点と線分の距離
+fn distance_to_segment(p: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq <= f32::EPSILON {
+        return (p - a).length();
     }
+    let t = ((p - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    (p - (a + ab * t)).length()
 }
 
 /// ノードの頻度から半径を決定 (ワールド座標)
@@ -604,6 +1706,47 @@ fn node_radius(freq: usize) -> f32 {
     6.0 + (freq as f32).ln_1p() * 4.5
 }
 
+/// ネットワーク描画領域の高さ
+const CANVAS_HEIGHT: f32 = 480.0;
+
+/// 「全体を表示」で描画領域の縁に残す余白 (画面 px)
+const FIT_MARGIN: f32 = 40.0;
+
+/// エッジをホバー中とみなすポインタからの距離 (画面 px)
+const EDGE_HOVER_DISTANCE: f32 = 5.0;
+
+/// 配置し直すときのネットワーク全体の半径 (ワールド座標)
+const LAYOUT_RADIUS: f32 = 200.0;
+
+/// ネットワーク描画領域の背景色
+const CANVAS_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(20, 22, 28);
+
+/// 凡例に表示するコミュニティの最大数
+const LEGEND_MAX: usize = 12;
+
+/// コミュニティの表示色 (単独ノードのコミュニティは灰色)
+fn community_color(community: usize, size: usize) -> egui::Color32 {
+    const PALETTE: [(u8, u8, u8); 12] = [
+        (102, 194, 165),
+        (252, 141, 98),
+        (141, 160, 203),
+        (231, 138, 195),
+        (166, 216, 84),
+        (255, 217, 47),
+        (229, 196, 148),
+        (78, 121, 167),
+        (242, 142, 43),
+        (225, 87, 89),
+        (118, 183, 178),
+        (175, 122, 161),
+    ];
+    if size <= 1 {
+        return egui::Color32::from_rgb(150, 150, 160);
+    }
+    let (r, g, b) = PALETTE[community % PALETTE.len()];
+    egui::Color32::from_rgb(r, g, b)
+}
+
 /// 2 色を線形補間する
 fn lerp_color(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
     let t = t.clamp(0.0, 1.0);
@@ -616,3 +1759,61 @@ fn lerp_color(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
         lerp(a.b(), b.b()),
     )
 }
+
+/// egui の色を描画内容の色に変換する
+fn to_rgba(color: egui::Color32) -> Rgba {
+    color.to_srgba_unmultiplied()
+}
+
+/// 描画内容を画面に描く (`origin` は描画領域の左上)
+fn paint_scene(painter: &egui::Painter, origin: egui::Pos2, scene: &NetworkScene) {
+    let pos = |(x, y): (f32, f32)| origin + egui::vec2(x, y);
+    let color = |[r, g, b, a]: Rgba| egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+    for shape in &scene.shapes {
+        match shape {
+            SceneShape::Line {
+                from,
+                to,
+                width,
+                color: c,
+            } => {
+                painter.line_segment([pos(*from), pos(*to)], egui::Stroke::new(*width, color(*c)));
+            }
+            SceneShape::Circle {
+                center,
+                radius,
+                fill,
+                stroke_width,
+                stroke,
+            } => {
+                painter.circle(
+                    pos(*center),
+                    *radius,
+                    color(*fill),
+                    egui::Stroke::new(*stroke_width, color(*stroke)),
+                );
+            }
+            SceneShape::Text {
+                position,
+                text,
+                size,
+                color: c,
+                anchor,
+            } => {
+                let align = match anchor {
+                    TextAnchor::CenterBottom => egui::Align2::CENTER_BOTTOM,
+                    TextAnchor::CenterTop => egui::Align2::CENTER_TOP,
+                    TextAnchor::LeftCenter => egui::Align2::LEFT_CENTER,
+                    TextAnchor::RightCenter => egui::Align2::RIGHT_CENTER,
+                };
+                painter.text(
+                    pos(*position),
+                    align,
+                    text,
+                    egui::FontId::proportional(*size),
+                    color(*c),
+                );
+            }
+        }
+    }
+}
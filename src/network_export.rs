@@ -0,0 +1,412 @@
+//! 共起ネットワークの外部形式 (GraphML / GEXF / DOT / JSON) への書き出し
+
+use crate::{CooccurrenceEdge, CooccurrenceNetwork};
+use serde_json::json;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+
+/// ネットワークの書き出し形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetworkFormat {
+    #[default]
+    GraphMl, // GraphML (Cytoscape / Gephi / yEd)
+    Gexf, // GEXF 1.3 (Gephi)
+    Dot,  // Graphviz DOT
+    Json, // node-link 形式の JSON (NetworkX / D3.js)
+}
+
+impl NetworkFormat {
+    pub const ALL: [NetworkFormat; 4] = [
+        NetworkFormat::GraphMl,
+        NetworkFormat::Gexf,
+        NetworkFormat::Dot,
+        NetworkFormat::Json,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NetworkFormat::GraphMl => "GraphML",
+            NetworkFormat::Gexf => "GEXF",
+            NetworkFormat::Dot => "Graphviz DOT",
+            NetworkFormat::Json => "JSON (node-link)",
+        }
+    }
+
+    /// ファイルの拡張子
+    pub fn extension(&self) -> &'static str {
+        match self {
+            NetworkFormat::GraphMl => "graphml",
+            NetworkFormat::Gexf => "gexf",
+            NetworkFormat::Dot => "dot",
+            NetworkFormat::Json => "json",
+        }
+    }
+}
+
+/// XML の属性値・テキストをエスケープする
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// 書き出すエッジの重み
+///
+/// PMI・NPMI などの関連度は負になりうるが、Gephi・Graphviz などは負の重みを扱えないため
+/// 中心性の計算と同じく 0 で切り捨てる。関連度そのものは `measure` 属性に書き出す。
+fn export_weight(edge: &CooccurrenceEdge) -> f64 {
+    edge.weight.max(0.0)
+}
+
+/// DOT の引用符付き文字列をエスケープする
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl CooccurrenceNetwork {
+    /// 指定形式の文字列に変換する
+    ///
+    /// `positions` はノードと同じ順のレイアウト座標 (画面と同じく y 軸は下向き)。
+    /// 書き出すコミュニティ番号は CSV・凡例と同じく 1 始まり。
+    pub fn export(&self, format: NetworkFormat, positions: Option<&[(f32, f32)]>) -> String {
+        let positions = positions.filter(|p| p.len() == self.nodes.len());
+        match format {
+            NetworkFormat::GraphMl => self.to_graphml(positions),
+            NetworkFormat::Gexf => self.to_gexf(positions),
+            NetworkFormat::Dot => self.to_dot(positions),
+            NetworkFormat::Json => self.to_node_link_json(positions),
+        }
+    }
+
+    /// 指定形式でファイルに保存
+    pub fn save_as(
+        &self,
+        format: NetworkFormat,
+        positions: Option<&[(f32, f32)]>,
+        path: std::path::PathBuf,
+    ) -> Result<(), std::io::Error> {
+        let mut file = File::create(path)?;
+        file.write_all(self.export(format, positions).as_bytes())
+    }
+
+    fn to_graphml(&self, positions: Option<&[(f32, f32)]>) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        let keys = [
+            ("label", "node", "string"),
+            ("frequency", "node", "int"),
+            ("community", "node", "int"),
+            ("degree", "node", "int"),
+            ("weighted_degree", "node", "double"),
+            ("betweenness", "node", "double"),
+            ("closeness", "node", "double"),
+            ("eigenvector", "node", "double"),
+            ("x", "node", "double"),
+            ("y", "node", "double"),
+            ("count", "edge", "int"),
+            ("weight", "edge", "double"),
+            ("measure", "edge", "double"),
+        ];
+        for (name, domain, ty) in keys {
+            let _ = writeln!(
+                out,
+                "  <key id=\"{name}\" for=\"{domain}\" attr.name=\"{name}\" attr.type=\"{ty}\"/>"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "  <graph id=\"cooccurrence\" edgedefault=\"undirected\">"
+        );
+        for (i, node) in self.nodes.iter().enumerate() {
+            let c = &node.centrality;
+            let _ = writeln!(out, "    <node id=\"n{i}\">");
+            let _ = writeln!(
+                out,
+                "      <data key=\"label\">{}</data>",
+                escape_xml(&node.word)
+            );
+            let _ = writeln!(
+                out,
+                "      <data key=\"frequency\">{}</data>",
+                node.frequency
+            );
+            let _ = writeln!(
+                out,
+                "      <data key=\"community\">{}</data>",
+                node.community + 1
+            );
+            let _ = writeln!(out, "      <data key=\"degree\">{}</data>", c.degree);
+            let _ = writeln!(
+                out,
+                "      <data key=\"weighted_degree\">{}</data>",
+                c.weighted_degree
+            );
+            let _ = writeln!(
+                out,
+                "      <data key=\"betweenness\">{}</data>",
+                c.betweenness
+            );
+            let _ = writeln!(out, "      <data key=\"closeness\">{}</data>", c.closeness);
+            let _ = writeln!(
+                out,
+                "      <data key=\"eigenvector\">{}</data>",
+                c.eigenvector
+            );
+            if let Some(positions) = positions {
+                let (x, y) = positions[i];
+                let _ = writeln!(out, "      <data key=\"x\">{x}</data>");
+                let _ = writeln!(out, "      <data key=\"y\">{y}</data>");
+            }
+            out.push_str("    </node>\n");
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "    <edge id=\"e{i}\" source=\"n{}\" target=\"n{}\">",
+                edge.source, edge.target
+            );
+            let _ = writeln!(out, "      <data key=\"count\">{}</data>", edge.count);
+            let _ = writeln!(
+                out,
+                "      <data key=\"weight\">{}</data>",
+                export_weight(edge)
+            );
+            let _ = writeln!(out, "      <data key=\"measure\">{}</data>", edge.weight);
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    fn to_gexf(&self, positions: Option<&[(f32, f32)]>) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(
+            "<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">\n",
+        );
+        let _ = writeln!(
+            out,
+            "  <meta>\n    <description>{}</description>\n  </meta>",
+            escape_xml(&format!(
+                "共起ネットワーク (エッジの重み: {})",
+                self.edge_measure.label()
+            ))
+        );
+        out.push_str("  <graph defaultedgetype=\"undirected\" mode=\"static\">\n");
+        out.push_str("    <attributes class=\"node\">\n");
+        let node_attributes = [
+            ("frequency", "integer"),
+            ("community", "integer"),
+            ("degree", "integer"),
+            ("weighted_degree", "double"),
+            ("betweenness", "double"),
+            ("closeness", "double"),
+            ("eigenvector", "double"),
+        ];
+        for (i, (name, ty)) in node_attributes.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "      <attribute id=\"{i}\" title=\"{name}\" type=\"{ty}\"/>"
+            );
+        }
+        out.push_str("    </attributes>\n");
+        out.push_str("    <attributes class=\"edge\">\n");
+        out.push_str("      <attribute id=\"0\" title=\"count\" type=\"integer\"/>\n");
+        out.push_str("      <attribute id=\"1\" title=\"measure\" type=\"double\"/>\n");
+        out.push_str("    </attributes>\n");
+
+        out.push_str("    <nodes>\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let c = &node.centrality;
+            let _ = writeln!(
+                out,
+                "      <node id=\"n{i}\" label=\"{}\">",
+                escape_xml(&node.word)
+            );
+            out.push_str("        <attvalues>\n");
+            let values = [
+                node.frequency.to_string(),
+                (node.community + 1).to_string(),
+                c.degree.to_string(),
+                c.weighted_degree.to_string(),
+                c.betweenness.to_string(),
+                c.closeness.to_string(),
+                c.eigenvector.to_string(),
+            ];
+            for (id, value) in values.iter().enumerate() {
+                let _ = writeln!(out, "          <attvalue for=\"{id}\" value=\"{value}\"/>");
+            }
+            out.push_str("        </attvalues>\n");
+            if let Some(positions) = positions {
+                // GEXF の y 軸は上向き
+                let (x, y) = positions[i];
+                let _ = writeln!(
+                    out,
+                    "        <viz:position x=\"{x}\" y=\"{}\" z=\"0.0\"/>",
+                    -y
+                );
+            }
+            out.push_str("      </node>\n");
+        }
+        out.push_str("    </nodes>\n");
+
+        out.push_str("    <edges>\n");
+        for (i, edge) in self.edges.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "      <edge id=\"e{i}\" source=\"n{}\" target=\"n{}\" weight=\"{}\">",
+                edge.source,
+                edge.target,
+                export_weight(edge)
+            );
+            out.push_str("        <attvalues>\n");
+            let _ = writeln!(
+                out,
+                "          <attvalue for=\"0\" value=\"{}\"/>",
+                edge.count
+            );
+            let _ = writeln!(
+                out,
+                "          <attvalue for=\"1\" value=\"{}\"/>",
+                edge.weight
+            );
+            out.push_str("        </attvalues>\n");
+            out.push_str("      </edge>\n");
+        }
+        out.push_str("    </edges>\n");
+        out.push_str("  </graph>\n</gexf>\n");
+        out
+    }
+
+    fn to_dot(&self, positions: Option<&[(f32, f32)]>) -> String {
+        let mut out = String::new();
+        out.push_str("graph cooccurrence {\n");
+        let _ = writeln!(
+            out,
+            "  graph [edge_measure=\"{}\", modularity=\"{}\"];",
+            escape_dot(self.edge_measure.label()),
+            self.modularity
+        );
+        for (i, node) in self.nodes.iter().enumerate() {
+            let c = &node.centrality;
+            let mut attributes = format!(
+                "label=\"{}\", frequency={}, community={}, degree={}, weighted_degree={}, betweenness={}, closeness={}, eigenvector={}",
+                escape_dot(&node.word),
+                node.frequency,
+                node.community + 1,
+                c.degree,
+                c.weighted_degree,
+                c.betweenness,
+                c.closeness,
+                c.eigenvector
+            );
+            if let Some(positions) = positions {
+                // Graphviz の座標はポイント単位で y 軸が上向き。"!" で位置を固定する
+                let (x, y) = positions[i];
+                let _ = write!(attributes, ", pos=\"{x},{}!\"", -y);
+            }
+            let _ = writeln!(out, "  n{i} [{attributes}];");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "  n{} -- n{} [count={}, weight={}, measure={}];",
+                edge.source,
+                edge.target,
+                edge.count,
+                export_weight(edge),
+                edge.weight
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_node_link_json(&self, positions: Option<&[(f32, f32)]>) -> String {
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let c = &node.centrality;
+                let mut value = json!({
+                    "id": i,
+                    "label": node.word,
+                    "frequency": node.frequency,
+                    "community": node.community + 1,
+                    "degree": c.degree,
+                    "weighted_degree": c.weighted_degree,
+                    "betweenness": c.betweenness,
+                    "closeness": c.closeness,
+                    "eigenvector": c.eigenvector,
+                });
+                if let Some(positions) = positions {
+                    let (x, y) = positions[i];
+                    value["x"] = json!(x);
+                    value["y"] = json!(y);
+                }
+                value
+            })
+            .collect();
+        let links: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "source": edge.source,
+                    "target": edge.target,
+                    "count": edge.count,
+                    "weight": export_weight(edge),
+                    "measure": edge.weight,
+                })
+            })
+            .collect();
+        let document = json!({
+            "directed": false,
+            "multigraph": false,
+            "graph": {
+                "edge_measure": self.edge_measure.label(),
+                "modularity": self.modularity,
+            },
+            "nodes": nodes,
+            "links": links,
+        });
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{network_from_edges, EdgeMeasure};
+
+    #[test]
+    fn negative_measure_is_exported_separately_from_weight() {
+        let mut network = network_from_edges(2, &[(0, 1, -0.5)]);
+        network.edges[0].count = 2;
+        network.edge_measure = EdgeMeasure::Pmi;
+        let graphml = network.export(NetworkFormat::GraphMl, None);
+        assert!(graphml.contains("<data key=\"weight\">0</data>"));
+        assert!(graphml.contains("<data key=\"measure\">-0.5</data>"));
+        let gexf = network.export(NetworkFormat::Gexf, None);
+        assert!(gexf.contains("weight=\"0\""));
+        assert!(gexf.contains("<attvalue for=\"1\" value=\"-0.5\"/>"));
+        let dot = network.export(NetworkFormat::Dot, None);
+        assert!(dot.contains("n0 -- n1 [count=2, weight=0, measure=-0.5];"));
+        let json: serde_json::Value =
+            serde_json::from_str(&network.export(NetworkFormat::Json, None)).unwrap();
+        assert_eq!(json["links"][0]["weight"], 0.0);
+        assert_eq!(json["links"][0]["measure"], -0.5);
+    }
+}
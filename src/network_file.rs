@@ -0,0 +1,471 @@
+//! 共起ネットワーク (計算条件・配置を含む) の保存と読み込み
+
+use crate::{
+    CommunityMethod, CooccurrenceConfig, CooccurrenceEdge, CooccurrenceNetwork, CooccurrenceNode,
+    CooccurrenceUnit, EdgeMeasure, Point, PosFilter, StructureUnit, UnitScope,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Write};
+
+/// ファイルの種類を表す識別子
+const NETWORK_FILE_FORMAT: &str = "jp_word_segment.network";
+/// ファイル形式のバージョン
+pub const NETWORK_FILE_VERSION: u64 = 2;
+/// 対応付けられなかったノードを既存ノードの近くに置くときの距離 (ワールド座標)
+const UNMATCHED_OFFSET: f32 = 30.0;
+
+/// 保存する共起ネットワーク一式
+#[derive(Debug, Clone, Default)]
+pub struct SavedNetwork {
+    pub config: CooccurrenceConfig,
+    pub network: CooccurrenceNetwork,
+    pub positions: Vec<Point>, // ノードと同じ順の配置
+    pub pinned: Vec<bool>,     // ノードと同じ順の固定状態
+}
+
+/// 保存した配置を別のネットワークに対応付けた結果
+#[derive(Debug, Clone)]
+pub struct MatchedLayout {
+    pub positions: Vec<Point>,
+    pub pinned: Vec<bool>,
+    pub matched: usize, // 単語で対応付けられたノード数
+}
+
+impl SavedNetwork {
+    /// JSON に変換する
+    pub fn to_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .network
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let (x, y) = self.positions.get(i).copied().unwrap_or((0.0, 0.0));
+                json!({
+                    "word": node.word,
+                    "frequency": node.frequency,
+                    "community": node.community,
+                    "x": x,
+                    "y": y,
+                    "pinned": self.pinned.get(i).copied().unwrap_or(false),
+                })
+            })
+            .collect();
+        let edges: Vec<Value> = self
+            .network
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "source": edge.source,
+                    "target": edge.target,
+                    "count": edge.count,
+                    "weight": edge.weight,
+                })
+            })
+            .collect();
+        json!({
+            "format": NETWORK_FILE_FORMAT,
+            "version": NETWORK_FILE_VERSION,
+            "config": config_to_json(&self.config),
+            "network": {
+                "edge_measure": enum_key(&self.network.edge_measure),
+                "modularity": self.network.modularity,
+                "units": self.network.units,
+                "nodes": nodes,
+                "edges": edges,
+            },
+        })
+    }
+
+    /// JSON から読み込む (中心性は読み込み時に計算し直す)
+    pub fn from_json(value: &Value) -> Result<Self, Error> {
+        if value["format"].as_str() != Some(NETWORK_FILE_FORMAT) {
+            return Err(invalid_data("共起ネットワークのファイルではありません"));
+        }
+        let version = value["version"].as_u64().unwrap_or(0);
+        if version > NETWORK_FILE_VERSION {
+            return Err(invalid_data(
+                "新しいバージョンで保存されたファイルのため読み込めません",
+            ));
+        }
+
+        let config = config_from_json(&value["config"]);
+        let network_value = &value["network"];
+        let mut saved = SavedNetwork {
+            config,
+            ..Default::default()
+        };
+        saved.network.edge_measure = network_value["edge_measure"]
+            .as_str()
+            .and_then(|key| enum_from_key(&EdgeMeasure::ALL, key))
+            .unwrap_or_default();
+        saved.network.modularity = network_value["modularity"].as_f64().unwrap_or(0.0);
+        saved.network.units = network_value["units"].as_u64().unwrap_or(0) as usize;
+
+        for node in network_value["nodes"].as_array().into_iter().flatten() {
+            let word = node["word"]
+                .as_str()
+                .ok_or_else(|| invalid_data("ノードの単語がありません"))?;
+            saved.network.nodes.push(CooccurrenceNode {
+                word: word.to_string(),
+                frequency: node["frequency"].as_u64().unwrap_or(0) as usize,
+                community: node["community"].as_u64().unwrap_or(0) as usize,
+                centrality: Default::default(),
+            });
+            saved.positions.push((
+                node["x"].as_f64().unwrap_or(0.0) as f32,
+                node["y"].as_f64().unwrap_or(0.0) as f32,
+            ));
+            saved.pinned.push(node["pinned"].as_bool().unwrap_or(false));
+        }
+
+        let n = saved.network.nodes.len();
+        for edge in network_value["edges"].as_array().into_iter().flatten() {
+            let source = edge["source"].as_u64().unwrap_or(u64::MAX) as usize;
+            let target = edge["target"].as_u64().unwrap_or(u64::MAX) as usize;
+            if source >= n || target >= n {
+                return Err(invalid_data("エッジが存在しないノードを参照しています"));
+            }
+            saved.network.edges.push(CooccurrenceEdge {
+                source,
+                target,
+                count: edge["count"].as_u64().unwrap_or(0) as usize,
+                weight: edge["weight"].as_f64().unwrap_or(0.0),
+            });
+        }
+
+        // コミュニティ番号は 0 から連続している前提のため、範囲外なら振り直す
+        if saved.network.nodes.iter().any(|node| node.community >= n) {
+            saved
+                .network
+                .detect_communities(saved.config.community_method);
+        }
+        saved.network.compute_centrality();
+        Ok(saved)
+    }
+
+    /// ファイルに保存 (JSON)
+    pub fn save(&self, path: std::path::PathBuf) -> Result<(), Error> {
+        let text = serde_json::to_string_pretty(&self.to_json())
+            .map_err(|e| Error::other(e.to_string()))?;
+        let mut file = File::create(path)?;
+        file.write_all(text.as_bytes())
+    }
+
+    /// ファイルから読み込む
+    pub fn load(path: std::path::PathBuf) -> Result<Self, Error> {
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| invalid_data(&e.to_string()))?;
+        Self::from_json(&value)
+    }
+
+    /// 保存した配置を別のネットワーク (テキストを修正して再計算したものなど) に単語で対応付ける
+    ///
+    /// 対応するノードがない単語は、対応付けられた隣接ノードの重心の近くに置く。
+    /// 隣接ノードも対応付けられない場合は保存した配置全体の重心の近くに置く。
+    pub fn layout_for(&self, network: &CooccurrenceNetwork) -> MatchedLayout {
+        let saved: HashMap<&str, usize> = self
+            .network
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.word.as_str(), i))
+            .collect();
+
+        let n = network.nodes.len();
+        let mut positions: Vec<Option<Point>> = vec![None; n];
+        let mut pinned = vec![false; n];
+        for (i, node) in network.nodes.iter().enumerate() {
+            if let Some(&j) = saved.get(node.word.as_str()) {
+                positions[i] = self.positions.get(j).copied();
+                pinned[i] = self.pinned.get(j).copied().unwrap_or(false);
+            }
+        }
+        let matched = positions.iter().filter(|p| p.is_some()).count();
+
+        let centroid = if self.positions.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let count = self.positions.len() as f32;
+            (
+                self.positions.iter().map(|p| p.0).sum::<f32>() / count,
+                self.positions.iter().map(|p| p.1).sum::<f32>() / count,
+            )
+        };
+        let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for edge in &network.edges {
+            neighbors[edge.source].push(edge.target);
+            neighbors[edge.target].push(edge.source);
+        }
+        let placed: Vec<Option<Point>> = positions.clone();
+        for (i, position) in positions.iter_mut().enumerate() {
+            if position.is_some() {
+                continue;
+            }
+            let anchors: Vec<Point> = neighbors[i].iter().filter_map(|&j| placed[j]).collect();
+            let base = if anchors.is_empty() {
+                centroid
+            } else {
+                let count = anchors.len() as f32;
+                (
+                    anchors.iter().map(|p| p.0).sum::<f32>() / count,
+                    anchors.iter().map(|p| p.1).sum::<f32>() / count,
+                )
+            };
+            // 黄金角ずつずらして重ならないようにする
+            let angle = i as f32 * 2.399_963;
+            *position = Some((
+                base.0 + UNMATCHED_OFFSET * angle.cos(),
+                base.1 + UNMATCHED_OFFSET * angle.sin(),
+            ));
+        }
+
+        MatchedLayout {
+            positions: positions.into_iter().flatten().collect(),
+            pinned,
+            matched,
+        }
+    }
+}
+
+pub(crate) fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// 列挙型の値を保存用の文字列にする (バリアント名)
+pub(crate) fn enum_key<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+/// 保存用の文字列から列挙型の値を得る
+pub(crate) fn enum_from_key<T: std::fmt::Debug + Copy>(all: &[T], key: &str) -> Option<T> {
+    all.iter().copied().find(|v| enum_key(v) == key)
+}
+
+/// 共起ネットワークの計算条件を JSON に変換する
+pub fn config_to_json(config: &CooccurrenceConfig) -> Value {
+    let mut stopwords: Vec<&String> = config.stopwords.iter().collect();
+    stopwords.sort();
+    let (unit, window_size) = match config.unit {
+        CooccurrenceUnit::Sentence => ("Sentence", None),
+        CooccurrenceUnit::Paragraph => ("Paragraph", None),
+        CooccurrenceUnit::Document => ("Document", None),
+        CooccurrenceUnit::Window(size) => ("Window", Some(size)),
+    };
+    json!({
+        "pos_filter": {
+            "include": config.pos_filter.include,
+            "exclude": config.pos_filter.exclude,
+        },
+        "min_word_freq": config.min_word_freq,
+        "min_edge_count": config.min_edge_count,
+        "max_nodes": config.max_nodes,
+        "stopwords": stopwords,
+        "edge_measure": enum_key(&config.edge_measure),
+        "min_edge_weight": config.min_edge_weight,
+        "max_edges": config.max_edges,
+        "unit": unit,
+        "window_size": window_size,
+        "community_method": enum_key(&config.community_method),
+        "scope": config.scope.as_ref().map(|scope| json!({
+            "unit": enum_key(&scope.unit),
+            "label": scope.label,
+        })),
+    })
+}
+
+/// JSON から共起ネットワークの計算条件を読み込む (欠けている項目は既定値)
+pub fn config_from_json(value: &Value) -> CooccurrenceConfig {
+    let mut config = CooccurrenceConfig::default();
+    let usize_field = |key: &str| value[key].as_u64().map(|v| v as usize);
+    let strings = |key: &str| -> Option<Vec<String>> {
+        value[key].as_array().map(|list| {
+            list.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+    };
+
+    let pos_filter = &value["pos_filter"];
+    if pos_filter.is_object() {
+        let rules = |key: &str| -> Vec<String> {
+            pos_filter[key]
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        config.pos_filter = PosFilter {
+            include: rules("include"),
+            exclude: rules("exclude"),
+        };
+    } else if let Some(target_pos) = strings("target_pos") {
+        // バージョン 1 は最上位の品詞の一覧のみ
+        config.pos_filter = PosFilter::including(&target_pos);
+    }
+    if let Some(v) = usize_field("min_word_freq") {
+        config.min_word_freq = v;
+    }
+    if let Some(v) = usize_field("min_edge_count") {
+        config.min_edge_count = v;
+    }
+    if let Some(v) = usize_field("max_nodes") {
+        config.max_nodes = v;
+    }
+    if let Some(stopwords) = strings("stopwords") {
+        config.stopwords = stopwords.into_iter().collect();
+    }
+    if let Some(measure) = value["edge_measure"]
+        .as_str()
+        .and_then(|key| enum_from_key(&EdgeMeasure::ALL, key))
+    {
+        config.edge_measure = measure;
+    }
+    if let Some(v) = value["min_edge_weight"].as_f64() {
+        config.min_edge_weight = v;
+    }
+    if let Some(v) = usize_field("max_edges") {
+        config.max_edges = v;
+    }
+    config.unit = match value["unit"].as_str() {
+        Some("Paragraph") => CooccurrenceUnit::Paragraph,
+        Some("Document") => CooccurrenceUnit::Document,
+        Some("Window") => CooccurrenceUnit::Window(usize_field("window_size").unwrap_or(5)),
+        _ => CooccurrenceUnit::Sentence,
+    };
+    if let Some(method) = value["community_method"]
+        .as_str()
+        .and_then(|key| enum_from_key(&CommunityMethod::ALL, key))
+    {
+        config.community_method = method;
+    }
+    let scope = &value["scope"];
+    config.scope = scope["unit"]
+        .as_str()
+        .and_then(|key| enum_from_key(&StructureUnit::ALL, key))
+        .zip(scope["label"].as_str())
+        .map(|(unit, label)| UnitScope {
+            unit,
+            label: label.to_string(),
+        });
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_from_edges;
+
+    /// 0 - 1 - 2 のパスグラフを (0, 0), (10, 0), (0, 10) に置き、1 を固定したもの
+    fn saved_network() -> SavedNetwork {
+        let mut network = network_from_edges(3, &[(0, 1, 0.5), (1, 2, 0.25)]);
+        network.nodes[2].community = 1;
+        network.edges[0].count = 3;
+        network.edge_measure = EdgeMeasure::Jaccard;
+        network.modularity = 0.125;
+        network.units = 4;
+        let config = CooccurrenceConfig {
+            edge_measure: EdgeMeasure::Jaccard,
+            unit: CooccurrenceUnit::Window(7),
+            community_method: CommunityMethod::Walktrap,
+            stopwords: ["する".to_string(), "ある".to_string()].into(),
+            scope: Some(UnitScope {
+                unit: StructureUnit::Section,
+                label: "第1章 / 第2節".to_string(),
+            }),
+            ..Default::default()
+        };
+        SavedNetwork {
+            config,
+            network,
+            positions: vec![(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)],
+            pinned: vec![false, true, false],
+        }
+    }
+
+    fn distance(a: Point, b: Point) -> f32 {
+        (a.0 - b.0).hypot(a.1 - b.1)
+    }
+
+    #[test]
+    fn saved_network_round_trips_through_json() {
+        let saved = saved_network();
+        let text = serde_json::to_string(&saved.to_json()).unwrap();
+        let loaded = SavedNetwork::from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+
+        assert_eq!(
+            config_to_json(&loaded.config),
+            config_to_json(&saved.config)
+        );
+        let nodes = |s: &SavedNetwork| -> Vec<(String, usize, usize)> {
+            s.network
+                .nodes
+                .iter()
+                .map(|n| (n.word.clone(), n.frequency, n.community))
+                .collect()
+        };
+        assert_eq!(nodes(&loaded), nodes(&saved));
+        let edges = |s: &SavedNetwork| -> Vec<(usize, usize, usize, f64)> {
+            s.network
+                .edges
+                .iter()
+                .map(|e| (e.source, e.target, e.count, e.weight))
+                .collect()
+        };
+        assert_eq!(edges(&loaded), edges(&saved));
+        assert_eq!(loaded.network.edge_measure, EdgeMeasure::Jaccard);
+        assert_eq!(loaded.network.modularity, 0.125);
+        assert_eq!(loaded.network.units, 4);
+        assert_eq!(loaded.positions, saved.positions);
+        assert_eq!(loaded.pinned, saved.pinned);
+        // 中心性は読み込み時に計算し直す
+        assert_eq!(loaded.network.nodes[1].centrality.degree, 2);
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let saved = saved_network();
+        let mut value = saved.to_json();
+        value["network"]["edges"][1]["target"] = json!(3);
+        assert!(SavedNetwork::from_json(&value).is_err());
+
+        let mut value = saved.to_json();
+        value["version"] = json!(NETWORK_FILE_VERSION + 1);
+        assert!(SavedNetwork::from_json(&value).is_err());
+
+        let mut value = saved.to_json();
+        value["format"] = json!("other");
+        assert!(SavedNetwork::from_json(&value).is_err());
+    }
+
+    #[test]
+    fn layout_is_matched_by_word() {
+        let saved = saved_network();
+        // 保存した "1", "2" と、"1" "2" につながる新しい語、孤立した新しい語
+        let mut network = network_from_edges(4, &[(0, 1, 1.0), (1, 3, 1.0)]);
+        for (node, word) in network.nodes.iter_mut().zip(["1", "新語", "孤立", "2"]) {
+            node.word = word.to_string();
+        }
+
+        let layout = saved.layout_for(&network);
+        assert_eq!(layout.matched, 2);
+        assert_eq!(layout.pinned, [true, false, false, false]);
+        assert_eq!(layout.positions[0], (10.0, 0.0));
+        assert_eq!(layout.positions[3], (0.0, 10.0));
+        // 対応付けられた隣接ノードの重心の近く
+        let offset = distance(layout.positions[1], (5.0, 5.0));
+        assert!((offset - UNMATCHED_OFFSET).abs() < 1e-3);
+        // 隣接ノードがなければ保存した配置全体の重心の近く
+        let offset = distance(layout.positions[2], (10.0 / 3.0, 10.0 / 3.0));
+        assert!((offset - UNMATCHED_OFFSET).abs() < 1e-3);
+    }
+}
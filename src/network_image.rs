@@ -0,0 +1,412 @@
+//! 共起ネットワークの描画内容を SVG / PNG 画像として書き出す
+
+use crate::network_export::escape_xml;
+use ab_glyph::{Font, FontRef, ScaleFont};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Write};
+use tiny_skia::{
+    FillRule, LineCap, Paint, PathBuilder, Pixmap, PremultipliedColorU8, Stroke, Transform,
+};
+
+/// 色 (RGBA, 乗算済みでない値)
+pub type Rgba = [u8; 4];
+
+/// テキストの基準点の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    CenterBottom, // 下端中央 (ノードの上に置くラベル)
+    CenterTop,    // 上端中央 (ノードの下に置くラベル)
+    LeftCenter,   // 左端の上下中央 (凡例・ノードの右に置くラベル)
+    RightCenter,  // 右端の上下中央 (ノードの左に置くラベル)
+}
+
+/// 描画要素 (座標は画像の左上を原点とするピクセル単位)
+#[derive(Debug, Clone)]
+pub enum SceneShape {
+    Line {
+        from: (f32, f32),
+        to: (f32, f32),
+        width: f32,
+        color: Rgba,
+    },
+    Circle {
+        center: (f32, f32),
+        radius: f32,
+        fill: Rgba,
+        stroke_width: f32,
+        stroke: Rgba,
+    },
+    Text {
+        position: (f32, f32),
+        text: String,
+        size: f32, // 行の高さ (egui のフォントサイズと同じ基準)
+        color: Rgba,
+        anchor: TextAnchor,
+    },
+}
+
+/// 画面・画像に共通の描画内容
+#[derive(Debug, Clone)]
+pub struct NetworkScene {
+    pub width: f32,
+    pub height: f32,
+    pub background: Rgba,
+    pub shapes: Vec<SceneShape>, // 描画順に並べる
+}
+
+/// SVG に指定するフォント名
+const SVG_FONT_FAMILY: &str = "Noto Sans JP";
+
+impl NetworkScene {
+    pub fn new(width: f32, height: f32, background: Rgba) -> Self {
+        Self {
+            width,
+            height,
+            background,
+            shapes: Vec::new(),
+        }
+    }
+
+    /// SVG 文字列に変換する
+    ///
+    /// `font` はラベルの描画に使うフォント (TrueType)。文字の大きさ・基準線の計算に用い、
+    /// `embed_font` が真なら SVG に埋め込んで閲覧環境にフォントがなくても同じ表示にする。
+    pub fn to_svg(&self, font: &[u8], embed_font: bool) -> Result<String, Error> {
+        let font_ref = parse_font(font)?;
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = self.width,
+            h = self.height
+        );
+        if embed_font {
+            let _ = writeln!(
+                out,
+                "<defs><style>@font-face {{ font-family: \"{}\"; src: url(data:font/ttf;base64,{}); }}</style></defs>",
+                SVG_FONT_FAMILY,
+                base64_encode(font)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "<rect width=\"100%\" height=\"100%\" {}/>",
+            svg_paint("fill", self.background)
+        );
+        let _ = writeln!(out, "<g font-family=\"'{}', sans-serif\">", SVG_FONT_FAMILY);
+
+        for shape in &self.shapes {
+            match shape {
+                SceneShape::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke-width=\"{:.2}\" stroke-linecap=\"round\" {}/>",
+                        from.0,
+                        from.1,
+                        to.0,
+                        to.1,
+                        width,
+                        svg_paint("stroke", *color)
+                    );
+                }
+                SceneShape::Circle {
+                    center,
+                    radius,
+                    fill,
+                    stroke_width,
+                    stroke,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {} stroke-width=\"{:.2}\" {}/>",
+                        center.0,
+                        center.1,
+                        radius,
+                        svg_paint("fill", *fill),
+                        stroke_width,
+                        svg_paint("stroke", *stroke)
+                    );
+                }
+                SceneShape::Text {
+                    position,
+                    text,
+                    size,
+                    color,
+                    anchor,
+                } => {
+                    let metrics = TextMetrics::new(&font_ref, *size);
+                    let middle = position.1 + (metrics.ascent + metrics.descent) / 2.0;
+                    let (text_anchor, baseline) = match anchor {
+                        TextAnchor::CenterBottom => ("middle", position.1 + metrics.descent),
+                        TextAnchor::CenterTop => ("middle", position.1 + metrics.ascent),
+                        TextAnchor::LeftCenter => ("start", middle),
+                        TextAnchor::RightCenter => ("end", middle),
+                    };
+                    let _ = writeln!(
+                        out,
+                        "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"{}\" {}>{}</text>",
+                        position.0,
+                        baseline,
+                        metrics.em_size,
+                        text_anchor,
+                        svg_paint("fill", *color),
+                        escape_xml(text)
+                    );
+                }
+            }
+        }
+        out.push_str("</g>\n</svg>\n");
+        Ok(out)
+    }
+
+    /// SVG ファイルとして保存
+    pub fn save_svg(
+        &self,
+        font: &[u8],
+        embed_font: bool,
+        path: std::path::PathBuf,
+    ) -> Result<(), Error> {
+        let svg = self.to_svg(font, embed_font)?;
+        let mut file = File::create(path)?;
+        file.write_all(svg.as_bytes())
+    }
+
+    /// PNG 画像 (幅・高さは `width` × `height` ピクセル) にラスタライズする
+    pub fn render_png(&self, font: &[u8]) -> Result<Vec<u8>, Error> {
+        let font_ref = parse_font(font)?;
+        let mut pixmap = Pixmap::new(
+            self.width.round().max(1.0) as u32,
+            self.height.round().max(1.0) as u32,
+        )
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "画像サイズが不正です"))?;
+        let [r, g, b, a] = self.background;
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+
+        for shape in &self.shapes {
+            match shape {
+                SceneShape::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => {
+                    let mut builder = PathBuilder::new();
+                    builder.move_to(from.0, from.1);
+                    builder.line_to(to.0, to.1);
+                    if let Some(path) = builder.finish() {
+                        let stroke = Stroke {
+                            width: *width,
+                            line_cap: LineCap::Round,
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(
+                            &path,
+                            &solid_paint(*color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                SceneShape::Circle {
+                    center,
+                    radius,
+                    fill,
+                    stroke_width,
+                    stroke,
+                } => {
+                    let Some(path) = PathBuilder::from_circle(center.0, center.1, *radius) else {
+                        continue;
+                    };
+                    pixmap.fill_path(
+                        &path,
+                        &solid_paint(*fill),
+                        FillRule::Winding,
+                        Transform::identity(),
+                        None,
+                    );
+                    if *stroke_width > 0.0 {
+                        let outline = Stroke {
+                            width: *stroke_width,
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(
+                            &path,
+                            &solid_paint(*stroke),
+                            &outline,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                SceneShape::Text {
+                    position,
+                    text,
+                    size,
+                    color,
+                    anchor,
+                } => draw_text(
+                    &mut pixmap,
+                    &font_ref,
+                    *position,
+                    text,
+                    *size,
+                    *color,
+                    *anchor,
+                ),
+            }
+        }
+
+        pixmap.encode_png().map_err(|e| Error::other(e.to_string()))
+    }
+
+    /// PNG ファイルとして保存
+    pub fn save_png(&self, font: &[u8], path: std::path::PathBuf) -> Result<(), Error> {
+        let png = self.render_png(font)?;
+        let mut file = File::create(path)?;
+        file.write_all(&png)
+    }
+}
+
+/// フォントデータを読み込む
+fn parse_font(font: &[u8]) -> Result<FontRef<'_>, Error> {
+    FontRef::try_from_slice(font).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+}
+
+/// 行の高さ `size` で描画するときのフォントの寸法 (ピクセル)
+struct TextMetrics {
+    ascent: f32,  // 基準線から上端まで
+    descent: f32, // 基準線から下端まで (負の値)
+    em_size: f32, // SVG の font-size に相当する 1em の大きさ
+}
+
+impl TextMetrics {
+    fn new(font: &FontRef<'_>, size: f32) -> Self {
+        let scaled = font.as_scaled(size);
+        let em_size = match font.units_per_em() {
+            Some(units) if font.height_unscaled() > 0.0 => size * units / font.height_unscaled(),
+            _ => size,
+        };
+        Self {
+            ascent: scaled.ascent(),
+            descent: scaled.descent(),
+            em_size,
+        }
+    }
+}
+
+/// 単色の塗り
+fn solid_paint(color: Rgba) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+    paint.anti_alias = true;
+    paint
+}
+
+/// SVG の fill / stroke 属性 (不透明でなければ opacity も付ける)
+fn svg_paint(attribute: &str, color: Rgba) -> String {
+    let [r, g, b, a] = color;
+    if a == 255 {
+        format!("{attribute}=\"rgb({r},{g},{b})\"")
+    } else {
+        format!(
+            "{attribute}=\"rgb({r},{g},{b})\" {attribute}-opacity=\"{:.3}\"",
+            a as f32 / 255.0
+        )
+    }
+}
+
+/// テキストをピクセルに直接合成する
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef<'_>,
+    position: (f32, f32),
+    text: &str,
+    size: f32,
+    color: Rgba,
+    anchor: TextAnchor,
+) {
+    let scaled = font.as_scaled(size);
+
+    // 文字送りを計算して全体の幅を求める
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut previous = None;
+    for ch in text.chars() {
+        let id = font.glyph_id(ch);
+        if let Some(prev) = previous {
+            caret += scaled.kern(prev, id);
+        }
+        glyphs.push((id, caret));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    let middle = position.1 + (scaled.ascent() + scaled.descent()) / 2.0;
+    let (x0, baseline) = match anchor {
+        TextAnchor::CenterBottom => (position.0 - caret / 2.0, position.1 + scaled.descent()),
+        TextAnchor::CenterTop => (position.0 - caret / 2.0, position.1 + scaled.ascent()),
+        TextAnchor::LeftCenter => (position.0, middle),
+        TextAnchor::RightCenter => (position.0 - caret, middle),
+    };
+
+    let (width, height) = (pixmap.width() as i64, pixmap.height() as i64);
+    let pixels = pixmap.pixels_mut();
+    let [r, g, b, a] = color;
+    for (id, offset) in glyphs {
+        let glyph = id.with_scale_and_position(size, ab_glyph::point(x0 + offset, baseline));
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let x = bounds.min.x as i64 + gx as i64;
+            let y = bounds.min.y as i64 + gy as i64;
+            if x < 0 || y < 0 || x >= width || y >= height {
+                return;
+            }
+            let alpha = coverage.clamp(0.0, 1.0) * a as f32 / 255.0;
+            let pixel = &mut pixels[(y * width + x) as usize];
+            let blend = |src: u8, dst: u8| -> u8 {
+                (src as f32 * alpha + dst as f32 * (1.0 - alpha))
+                    .round()
+                    .clamp(0.0, 255.0) as u8
+            };
+            let out_a = blend(255, pixel.alpha());
+            let out_r = blend(r, pixel.red()).min(out_a);
+            let out_g = blend(g, pixel.green()).min(out_a);
+            let out_b = blend(b, pixel.blue()).min(out_a);
+            if let Some(blended) = PremultipliedColorU8::from_rgba(out_r, out_g, out_b, out_a) {
+                *pixel = blended;
+            }
+        });
+    }
+}
+
+/// Base64 エンコード (フォント埋め込み用)
+fn base64_encode(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
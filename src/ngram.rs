@@ -0,0 +1,268 @@
+//! N-gram (語連鎖) / 語彙束 (lexical bundle) の抽出
+
+use crate::{
+    escape_csv_field, split_documents, split_sentences, DocumentSplit, TokenInfo, TokenizerCore,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+
+/// n-gram 抽出の条件
+#[derive(Debug, Clone)]
+pub struct NgramConfig {
+    pub min_n: usize,                  // 最小語数 (2..=6)
+    pub max_n: usize,                  // 最大語数 (2..=6)
+    pub min_freq: usize,               // 最小出現頻度
+    pub min_range: usize,              // 最小レンジ (出現文書数)
+    pub document_split: DocumentSplit, // レンジ計算に使う文書の区切り方
+    pub pos_pattern: Vec<String>,      // 品詞パターン (例: ["名詞", "の", "名詞"])。空なら制限なし
+    pub within_sentence: bool,         // 文境界をまたぐ n-gram を除外する
+}
+
+impl Default for NgramConfig {
+    fn default() -> Self {
+        Self {
+            min_n: 2,
+            max_n: 4,
+            min_freq: 2,
+            min_range: 1,
+            document_split: DocumentSplit::Whole,
+            pos_pattern: Vec::new(),
+            within_sentence: true,
+        }
+    }
+}
+
+impl NgramConfig {
+    pub const MIN_N: usize = 2;
+    pub const MAX_N: usize = 6;
+
+    /// "名詞+の+名詞" 形式の文字列から品詞パターンを設定する
+    ///
+    /// 各要素は品詞 (例: 名詞)・表層形 (例: の)・任意の 1 語を表す `*` のいずれか。
+    pub fn set_pos_pattern(&mut self, pattern: &str) {
+        self.pos_pattern = parse_pos_pattern(pattern);
+    }
+}
+
+/// n-gram の集計結果
+#[derive(Debug, Clone)]
+pub struct NgramEntry {
+    pub words: Vec<String>, // 構成語
+    pub pos: Vec<String>,   // 構成語の品詞
+    pub frequency: usize,   // 出現頻度
+    pub range: usize,       // 出現文書数
+}
+
+impl NgramEntry {
+    /// 構成語を空白区切りで連結した表記
+    pub fn text(&self) -> String {
+        self.words.join(" ")
+    }
+
+    /// 品詞列を "+" 区切りで連結した表記
+    pub fn pos_text(&self) -> String {
+        self.pos.join("+")
+    }
+}
+
+/// "名詞+の+名詞" 形式のパターン文字列を要素に分解する
+fn parse_pos_pattern(pattern: &str) -> Vec<String> {
+    pattern
+        .split(['+', '＋'])
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// パターン要素がトークンに一致するか (品詞・表層形・ワイルドカード)
+fn pattern_matches(pattern: &[String], tokens: &[TokenInfo]) -> bool {
+    pattern.len() == tokens.len()
+        && pattern
+            .iter()
+            .zip(tokens)
+            .all(|(p, t)| p == "*" || p == &t.pos || p == &t.text)
+}
+
+/// トークン列から長さ `min_n..=max_n` の n-gram を取り出す (空白トークンをまたがない)
+///
+/// `pattern` が空でなければ、パターンに一致するものだけを返す。
+fn ngram_windows<'a>(
+    tokens: &'a [TokenInfo],
+    min_n: usize,
+    max_n: usize,
+    pattern: &'a [String],
+) -> impl Iterator<Item = &'a [TokenInfo]> {
+    tokens
+        .split(|t| t.text.trim().is_empty())
+        .flat_map(move |segment| (min_n..=max_n).flat_map(move |n| segment.windows(n)))
+        .filter(move |window| pattern.is_empty() || pattern_matches(pattern, window))
+}
+
+impl TokenizerCore {
+    /// 入力テキストから n-gram を抽出する
+    ///
+    /// 空白トークンは n-gram の区切りとして扱う。`within_sentence` が真の場合は
+    /// 文単位 (。!? および改行で区切る) で解析し、文をまたぐ n-gram を数えない。
+    /// 結果は頻度の降順 (同頻度はレンジの降順) で返す。
+    pub fn compute_ngrams(&self, config: &NgramConfig) -> Vec<NgramEntry> {
+        let (min_n, max_n) = if config.pos_pattern.is_empty() {
+            let min_n = config.min_n.clamp(NgramConfig::MIN_N, NgramConfig::MAX_N);
+            (min_n, config.max_n.clamp(min_n, NgramConfig::MAX_N))
+        } else {
+            // パターン指定時は長さをパターンに合わせる
+            (config.pos_pattern.len(), config.pos_pattern.len())
+        };
+
+        let documents = split_documents(&self.input_text, config.document_split);
+
+        // キー: 構成語列 → (品詞列, 頻度, 出現文書集合)
+        let mut counts: HashMap<Vec<String>, (Vec<String>, usize, HashSet<usize>)> = HashMap::new();
+
+        for (doc_idx, document) in documents.iter().enumerate() {
+            let units = if config.within_sentence {
+                split_sentences(document)
+            } else {
+                vec![document.clone()]
+            };
+
+            for unit in &units {
+                let tokens = self.tokenize_to_infos(unit);
+                for window in ngram_windows(&tokens, min_n, max_n, &config.pos_pattern) {
+                    // ストップワードを含む n-gram は数えない
+                    if window.iter().any(|t| self.is_stopword(&t.text)) {
+                        continue;
+                    }
+                    let words: Vec<String> = window.iter().map(|t| t.text.clone()).collect();
+                    let entry = counts.entry(words).or_insert_with(|| {
+                        (
+                            window.iter().map(|t| t.pos.clone()).collect(),
+                            0,
+                            HashSet::new(),
+                        )
+                    });
+                    entry.1 += 1;
+                    entry.2.insert(doc_idx);
+                }
+            }
+        }
+
+        let mut entries: Vec<NgramEntry> = counts
+            .into_iter()
+            .map(|(words, (pos, frequency, docs))| NgramEntry {
+                words,
+                pos,
+                frequency,
+                range: docs.len(),
+            })
+            .filter(|e| e.frequency >= config.min_freq && e.range >= config.min_range)
+            .collect();
+        entries.sort_by(|a, b| {
+            b.frequency
+                .cmp(&a.frequency)
+                .then_with(|| b.range.cmp(&a.range))
+                .then_with(|| a.words.cmp(&b.words))
+        });
+        entries
+    }
+}
+
+/// n-gram の集計結果を CSV ファイルに保存 (UTF-8 BOM 付き)
+pub fn save_ngrams_to_csv(
+    entries: &[NgramEntry],
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    writeln!(file, "n-gram,語数,品詞列,出現頻度,レンジ")?;
+    for entry in entries {
+        writeln!(
+            file,
+            "{},{},{},{},{}",
+            escape_csv_field(&entry.text()),
+            entry.words.len(),
+            escape_csv_field(&entry.pos_text()),
+            entry.frequency,
+            entry.range
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens_from_features;
+
+    /// 「東京の大学 京都の大学」
+    fn tokens() -> Vec<TokenInfo> {
+        tokens_from_features(&[
+            (
+                "東京",
+                "名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー",
+            ),
+            ("の", "助詞,連体化,*,*,*,*,の,ノ,ノ"),
+            ("大学", "名詞,一般,*,*,*,*,大学,ダイガク,ダイガク"),
+            (" ", "記号,空白,*,*,*,*, ,*,*"),
+            ("京都", "名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート"),
+            ("の", "助詞,連体化,*,*,*,*,の,ノ,ノ"),
+            ("大学", "名詞,一般,*,*,*,*,大学,ダイガク,ダイガク"),
+        ])
+    }
+
+    fn texts<'a>(windows: impl Iterator<Item = &'a [TokenInfo]>) -> Vec<String> {
+        windows
+            .map(|w| {
+                w.iter()
+                    .map(|t| t.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pattern_is_split_on_plus_signs() {
+        assert_eq!(parse_pos_pattern("名詞+の+名詞"), ["名詞", "の", "名詞"]);
+        assert_eq!(parse_pos_pattern(" 名詞 ＋ * ++"), ["名詞", "*"]);
+        assert!(parse_pos_pattern("").is_empty());
+    }
+
+    #[test]
+    fn pattern_matches_pos_surface_and_wildcard() {
+        let tokens = tokens();
+        let pattern = parse_pos_pattern;
+        assert!(pattern_matches(&pattern("名詞+の+名詞"), &tokens[..3]));
+        assert!(pattern_matches(&pattern("名詞+助詞+*"), &tokens[..3]));
+        assert!(pattern_matches(&pattern("東京+*+大学"), &tokens[..3]));
+        assert!(!pattern_matches(&pattern("名詞+が+名詞"), &tokens[..3]));
+        // 長さが違えば一致しない
+        assert!(!pattern_matches(&pattern("名詞+の"), &tokens[..3]));
+    }
+
+    #[test]
+    fn ngrams_do_not_cross_whitespace() {
+        let tokens = tokens();
+        assert_eq!(
+            texts(ngram_windows(&tokens, 2, 3, &[])),
+            [
+                "東京 の",
+                "の 大学",
+                "東京 の 大学",
+                "京都 の",
+                "の 大学",
+                "京都 の 大学",
+            ]
+        );
+        let pattern = parse_pos_pattern("*+名詞");
+        assert_eq!(
+            texts(ngram_windows(&tokens, 2, 2, &pattern)),
+            ["の 大学", "の 大学"]
+        );
+    }
+}
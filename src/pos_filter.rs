@@ -0,0 +1,219 @@
+//! IPADIC の品詞階層による絞り込み
+
+/// IPADIC の品詞体系 (細分類までを "-" で連結したもの)
+pub const IPADIC_POS: [&str; 69] = [
+    "名詞-一般",
+    "名詞-固有名詞-一般",
+    "名詞-固有名詞-人名-一般",
+    "名詞-固有名詞-人名-姓",
+    "名詞-固有名詞-人名-名",
+    "名詞-固有名詞-組織",
+    "名詞-固有名詞-地域-一般",
+    "名詞-固有名詞-地域-国",
+    "名詞-代名詞-一般",
+    "名詞-代名詞-縮約",
+    "名詞-副詞可能",
+    "名詞-サ変接続",
+    "名詞-形容動詞語幹",
+    "名詞-数",
+    "名詞-非自立-一般",
+    "名詞-非自立-副詞可能",
+    "名詞-非自立-助動詞語幹",
+    "名詞-非自立-形容動詞語幹",
+    "名詞-特殊-助動詞語幹",
+    "名詞-接尾-一般",
+    "名詞-接尾-人名",
+    "名詞-接尾-地域",
+    "名詞-接尾-サ変接続",
+    "名詞-接尾-助動詞語幹",
+    "名詞-接尾-形容動詞語幹",
+    "名詞-接尾-副詞可能",
+    "名詞-接尾-助数詞",
+    "名詞-接尾-特殊",
+    "名詞-接続詞的",
+    "名詞-動詞非自立的",
+    "名詞-引用文字列",
+    "名詞-ナイ形容詞語幹",
+    "動詞-自立",
+    "動詞-非自立",
+    "動詞-接尾",
+    "形容詞-自立",
+    "形容詞-非自立",
+    "形容詞-接尾",
+    "副詞-一般",
+    "副詞-助詞類接続",
+    "連体詞",
+    "接続詞",
+    "感動詞",
+    "接頭詞-名詞接続",
+    "接頭詞-動詞接続",
+    "接頭詞-形容詞接続",
+    "接頭詞-数接続",
+    "助詞-格助詞-一般",
+    "助詞-格助詞-引用",
+    "助詞-格助詞-連語",
+    "助詞-接続助詞",
+    "助詞-係助詞",
+    "助詞-副助詞",
+    "助詞-間投助詞",
+    "助詞-並立助詞",
+    "助詞-終助詞",
+    "助詞-副助詞／並立助詞／終助詞",
+    "助詞-連体化",
+    "助詞-副詞化",
+    "助詞-特殊",
+    "助動詞",
+    "記号-一般",
+    "記号-句点",
+    "記号-読点",
+    "記号-空白",
+    "記号-アルファベット",
+    "記号-括弧開",
+    "記号-括弧閉",
+    "フィラー",
+];
+
+/// 品詞の絞り込み条件
+///
+/// 品詞は "名詞-非自立-一般" のように階層を "-" で連結したパスで指定し、上位の品詞を指定すると
+/// その下位の品詞もすべて含む。トークンには一致する規則のうち最も深いものを適用し、
+/// 同じ深さでは除外を優先する。対象にする品詞が空なら、除外した品詞以外をすべて対象にする。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PosFilter {
+    pub include: Vec<String>, // 対象にする品詞 (空ならすべて)
+    pub exclude: Vec<String>, // 除外する品詞
+}
+
+impl PosFilter {
+    /// 指定した品詞をすべて対象にする条件
+    pub fn including<S: AsRef<str>>(paths: &[S]) -> Self {
+        Self {
+            include: paths.iter().map(|p| p.as_ref().to_string()).collect(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// 除外する品詞を追加する
+    pub fn excluding<S: AsRef<str>>(mut self, paths: &[S]) -> Self {
+        self.exclude
+            .extend(paths.iter().map(|p| p.as_ref().to_string()));
+        self
+    }
+
+    /// 品詞パス (上位の階層だけでもよい) が対象か
+    pub fn matches(&self, pos_path: &str) -> bool {
+        let deepest = |rules: &[String]| {
+            rules
+                .iter()
+                .filter(|rule| is_under(pos_path, rule))
+                .map(|rule| depth(rule))
+                .max()
+        };
+        // 対象が空なら最上位より上の規則ですべてを対象にする
+        let include = if self.include.is_empty() {
+            Some(0)
+        } else {
+            deepest(&self.include)
+        };
+        match (include, deepest(&self.exclude)) {
+            (Some(include), Some(exclude)) => include > exclude,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+
+    /// 品詞を対象にする・除外する (下位の品詞の規則は取り除き、上位と同じ状態になる)
+    pub fn set(&mut self, path: &str, selected: bool) {
+        self.include.retain(|rule| !is_under(rule, path));
+        self.exclude.retain(|rule| !is_under(rule, path));
+        if self.matches(path) != selected {
+            if selected {
+                self.include.push(path.to_string());
+            } else {
+                self.exclude.push(path.to_string());
+            }
+        }
+    }
+
+    /// 下位に状態の異なる品詞があるか
+    pub fn is_mixed(&self, path: &str) -> bool {
+        let selected = self.matches(path);
+        self.include
+            .iter()
+            .chain(&self.exclude)
+            .any(|rule| rule != path && is_under(rule, path) && self.matches(rule) != selected)
+    }
+}
+
+/// 品詞の階層で `parent` の直下にある品詞 (`None` なら最上位の品詞)
+pub fn ipadic_pos_children(parent: Option<&str>) -> Vec<String> {
+    let level = parent.map(depth).unwrap_or(0);
+    let mut children: Vec<String> = Vec::new();
+    for path in IPADIC_POS {
+        if parent.map(|p| is_under(path, p)).unwrap_or(true) {
+            let child: Vec<&str> = path.split('-').take(level + 1).collect();
+            if child.len() > level {
+                let child = child.join("-");
+                if !children.contains(&child) {
+                    children.push(child);
+                }
+            }
+        }
+    }
+    children
+}
+
+/// `path` が `rule` と同じか、その下位の品詞か
+fn is_under(path: &str, rule: &str) -> bool {
+    path == rule
+        || path
+            .strip_prefix(rule)
+            .is_some_and(|rest| rest.starts_with('-'))
+}
+
+/// 品詞パスの階層の深さ
+fn depth(path: &str) -> usize {
+    path.split('-').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upper_pos_includes_its_subcategories() {
+        let filter = PosFilter::including(&["名詞", "動詞-自立"]);
+        assert!(filter.matches("名詞"));
+        assert!(filter.matches("名詞-固有名詞-人名-姓"));
+        assert!(filter.matches("動詞-自立"));
+        assert!(!filter.matches("動詞"));
+        assert!(!filter.matches("動詞-非自立"));
+        // 文字列の前方一致ではなく階層で比べる
+        assert!(!PosFilter::including(&["名詞-数"]).matches("名詞-数接続"));
+    }
+
+    #[test]
+    fn exclude_overrides_include_at_the_same_or_deeper_level() {
+        let filter = PosFilter::including(&["名詞", "名詞-非自立-一般"])
+            .excluding(&["名詞-非自立", "名詞-数"]);
+        assert!(filter.matches("名詞-一般"));
+        assert!(!filter.matches("名詞-数"));
+        assert!(!filter.matches("名詞-非自立-副詞可能"));
+        // より深い対象の指定は除外より優先する
+        assert!(filter.matches("名詞-非自立-一般"));
+
+        let same = PosFilter::including(&["助詞"]).excluding(&["助詞"]);
+        assert!(!same.matches("助詞-係助詞"));
+    }
+
+    #[test]
+    fn empty_include_matches_everything_not_excluded() {
+        let filter = PosFilter::default();
+        assert!(filter.matches("名詞-一般"));
+        assert!(filter.matches("記号-句点"));
+
+        let filter = filter.excluding(&["記号"]);
+        assert!(filter.matches("助詞-格助詞-一般"));
+        assert!(!filter.matches("記号-句点"));
+    }
+}
@@ -0,0 +1,329 @@
+//! プロジェクト (テキスト・設定・分析条件一式) の保存と読み込み
+
+use crate::network_file::{
+    config_from_json, config_to_json, enum_from_key, enum_key, invalid_data,
+};
+use crate::{
+    CompoundConfig, CooccurrenceConfig, LayoutMethod, SavedNetwork, SegmentMode, StructureConfig,
+    StructureUnit, TokenizerCore, TokenizerSettings,
+};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Error, Read, Write};
+
+/// ファイルの種類を表す識別子
+const PROJECT_FILE_FORMAT: &str = "jp_word_segment.project";
+/// ファイル形式のバージョン
+pub const PROJECT_FILE_VERSION: u64 = 1;
+
+/// コンコーダンス検索の条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcordanceQuery {
+    pub keyword: String,
+    pub context_size: usize,
+    pub unit: Option<StructureUnit>, // 結果を分類する構造の単位
+}
+
+impl Default for ConcordanceQuery {
+    fn default() -> Self {
+        Self {
+            keyword: String::new(),
+            context_size: 5,
+            unit: None,
+        }
+    }
+}
+
+/// 共起ネットワークのビューの状態
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSettings {
+    pub config: CooccurrenceConfig, // 設定 UI の条件 (まだ計算していないものを含む)
+    pub layout_method: LayoutMethod,
+    pub layout_seed: u64,
+    pub saved: Option<SavedNetwork>, // 計算済みのネットワークと配置
+}
+
+/// 保存するプロジェクト一式
+///
+/// 解析結果は保存せず、読み込み時に保存した設定で解析し直す。
+#[derive(Debug, Clone, Default)]
+pub struct Project {
+    pub input_text: String,
+    pub file_path: Option<String>, // テキストを読み込んだファイル
+    pub analyzed: bool,            // 解析済みだったか
+    pub tokenizer: TokenizerSettings,
+    pub stopwords: HashSet<String>,
+    pub structure_config: StructureConfig,
+    pub concordance: ConcordanceQuery,
+    pub network: NetworkSettings,
+}
+
+impl Project {
+    /// TokenizerCore の状態からプロジェクトを作る (ビューの状態は既定値)
+    pub fn from_core(core: &TokenizerCore) -> Self {
+        Self {
+            input_text: core.input_text.clone(),
+            file_path: core.file_path.clone(),
+            analyzed: !core.tokens.is_empty(),
+            tokenizer: core.settings.clone(),
+            stopwords: core.stopwords.clone(),
+            structure_config: core.structure_config.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// TokenizerCore の状態を復元し、解析済みだった場合は解析し直す
+    ///
+    /// ユーザー辞書を読み込めないなど形態素解析器の設定を適用できない場合は、
+    /// 既定の設定で残りを復元した上でエラーを返す。
+    pub fn restore_core(&self, core: &mut TokenizerCore) -> Result<(), Error> {
+        let tokenizer_result = core
+            .set_tokenizer_settings(self.tokenizer.clone())
+            .or_else(|e| {
+                core.set_tokenizer_settings(TokenizerSettings::default())
+                    .map_err(|e| Error::other(e.to_string()))?;
+                Err(Error::other(format!(
+                    "形態素解析器の設定を適用できません: {}",
+                    e
+                )))
+            });
+
+        core.input_text = self.input_text.clone();
+        core.file_path = self.file_path.clone();
+        core.tokens.clear();
+        core.stopwords = self.stopwords.clone();
+        core.recount_frequencies();
+        if core
+            .set_structure_config(self.structure_config.clone())
+            .is_err()
+        {
+            core.structure_config = StructureConfig::default();
+        }
+        if self.analyzed {
+            core.analyze_text();
+        }
+        tokenizer_result
+    }
+
+    /// JSON に変換する
+    pub fn to_json(&self) -> Value {
+        let mut stopwords: Vec<&String> = self.stopwords.iter().collect();
+        stopwords.sort();
+        json!({
+            "format": PROJECT_FILE_FORMAT,
+            "version": PROJECT_FILE_VERSION,
+            "text": {
+                "input_text": self.input_text,
+                "file_path": self.file_path,
+                "analyzed": self.analyzed,
+            },
+            "tokenizer": {
+                "mode": enum_key(&self.tokenizer.mode),
+                "user_dictionary": self.tokenizer.user_dictionary,
+                "compound": {
+                    "enabled": self.tokenizer.compound.enabled,
+                    "prefixes": self.tokenizer.compound.prefixes,
+                    "suffixes": self.tokenizer.compound.suffixes,
+                    "numbers": self.tokenizer.compound.numbers,
+                },
+            },
+            "stopwords": stopwords,
+            "structure": {
+                "chapter_patterns": self.structure_config.chapter_patterns,
+                "section_patterns": self.structure_config.section_patterns,
+            },
+            "concordance": {
+                "keyword": self.concordance.keyword,
+                "context_size": self.concordance.context_size,
+                "unit": self.concordance.unit.as_ref().map(enum_key),
+            },
+            "network": {
+                "config": config_to_json(&self.network.config),
+                "layout_method": enum_key(&self.network.layout_method),
+                "layout_seed": self.network.layout_seed,
+                "saved": self.network.saved.as_ref().map(|saved| saved.to_json()),
+            },
+        })
+    }
+
+    /// JSON から読み込む (欠けている項目は既定値)
+    pub fn from_json(value: &Value) -> Result<Self, Error> {
+        if value["format"].as_str() != Some(PROJECT_FILE_FORMAT) {
+            return Err(invalid_data("プロジェクトのファイルではありません"));
+        }
+        let version = value["version"].as_u64().unwrap_or(0);
+        if version > PROJECT_FILE_VERSION {
+            return Err(invalid_data(
+                "新しいバージョンで保存されたファイルのため読み込めません",
+            ));
+        }
+
+        let strings = |value: &Value| -> Option<Vec<String>> {
+            value.as_array().map(|list| {
+                list.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+        };
+        let mut project = Project::default();
+
+        let text = &value["text"];
+        project.input_text = text["input_text"].as_str().unwrap_or("").to_string();
+        project.file_path = text["file_path"].as_str().map(|s| s.to_string());
+        project.analyzed = text["analyzed"].as_bool().unwrap_or(false);
+
+        let tokenizer = &value["tokenizer"];
+        let compound = &tokenizer["compound"];
+        let default_compound = CompoundConfig::default();
+        let flag = |key: &str, default: bool| compound[key].as_bool().unwrap_or(default);
+        project.tokenizer = TokenizerSettings {
+            mode: tokenizer["mode"]
+                .as_str()
+                .and_then(|key| enum_from_key(&SegmentMode::ALL, key))
+                .unwrap_or_default(),
+            user_dictionary: tokenizer["user_dictionary"].as_str().map(|s| s.to_string()),
+            compound: CompoundConfig {
+                enabled: flag("enabled", default_compound.enabled),
+                prefixes: flag("prefixes", default_compound.prefixes),
+                suffixes: flag("suffixes", default_compound.suffixes),
+                numbers: flag("numbers", default_compound.numbers),
+            },
+        };
+
+        if let Some(stopwords) = strings(&value["stopwords"]) {
+            project.stopwords = stopwords.into_iter().collect();
+        }
+
+        let structure = &value["structure"];
+        if let Some(patterns) = strings(&structure["chapter_patterns"]) {
+            project.structure_config.chapter_patterns = patterns;
+        }
+        if let Some(patterns) = strings(&structure["section_patterns"]) {
+            project.structure_config.section_patterns = patterns;
+        }
+
+        let concordance = &value["concordance"];
+        if let Some(keyword) = concordance["keyword"].as_str() {
+            project.concordance.keyword = keyword.to_string();
+        }
+        if let Some(size) = concordance["context_size"].as_u64() {
+            project.concordance.context_size = size as usize;
+        }
+        project.concordance.unit = concordance["unit"]
+            .as_str()
+            .and_then(|key| enum_from_key(&StructureUnit::ALL, key));
+
+        let network = &value["network"];
+        if network["config"].is_object() {
+            project.network.config = config_from_json(&network["config"]);
+        }
+        project.network.layout_method = network["layout_method"]
+            .as_str()
+            .and_then(|key| enum_from_key(&LayoutMethod::ALL, key))
+            .unwrap_or_default();
+        project.network.layout_seed = network["layout_seed"].as_u64().unwrap_or(1);
+        if !network["saved"].is_null() {
+            project.network.saved = Some(SavedNetwork::from_json(&network["saved"])?);
+        }
+
+        Ok(project)
+    }
+
+    /// ファイルに保存する (JSON)
+    pub fn save(&self, path: std::path::PathBuf) -> Result<(), Error> {
+        let text = serde_json::to_string_pretty(&self.to_json())
+            .map_err(|e| Error::other(e.to_string()))?;
+        let mut file = File::create(path)?;
+        file.write_all(text.as_bytes())
+    }
+
+    /// ファイルから読み込む
+    pub fn load(path: std::path::PathBuf) -> Result<Self, Error> {
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| invalid_data(&e.to_string()))?;
+        Self::from_json(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{network_from_edges, CooccurrenceUnit};
+
+    #[test]
+    fn project_round_trips_through_json() {
+        let project = Project {
+            input_text: "第1章\n本文".to_string(),
+            file_path: Some("/tmp/本文.txt".to_string()),
+            analyzed: true,
+            tokenizer: TokenizerSettings {
+                mode: SegmentMode::Decompose,
+                user_dictionary: Some("/tmp/user.csv".to_string()),
+                compound: CompoundConfig {
+                    enabled: true,
+                    prefixes: false,
+                    suffixes: true,
+                    numbers: false,
+                },
+            },
+            stopwords: ["こと".to_string(), "もの".to_string()].into(),
+            structure_config: StructureConfig {
+                chapter_patterns: vec!["^第.章".to_string()],
+                section_patterns: Vec::new(),
+            },
+            concordance: ConcordanceQuery {
+                keyword: "本文".to_string(),
+                context_size: 8,
+                unit: Some(StructureUnit::Paragraph),
+            },
+            network: NetworkSettings {
+                config: CooccurrenceConfig {
+                    unit: CooccurrenceUnit::Paragraph,
+                    ..Default::default()
+                },
+                layout_method: LayoutMethod::Mds,
+                layout_seed: 42,
+                saved: Some(SavedNetwork {
+                    network: network_from_edges(2, &[(0, 1, 1.0)]),
+                    positions: vec![(1.0, 2.0), (3.0, 4.0)],
+                    pinned: vec![true, false],
+                    ..Default::default()
+                }),
+            },
+        };
+        let text = serde_json::to_string(&project.to_json()).unwrap();
+        let loaded = Project::from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+
+        assert_eq!(loaded.input_text, project.input_text);
+        assert_eq!(loaded.file_path, project.file_path);
+        assert!(loaded.analyzed);
+        assert_eq!(loaded.tokenizer, project.tokenizer);
+        assert_eq!(loaded.stopwords, project.stopwords);
+        assert_eq!(loaded.structure_config, project.structure_config);
+        assert_eq!(loaded.concordance, project.concordance);
+        assert_eq!(
+            config_to_json(&loaded.network.config),
+            config_to_json(&project.network.config)
+        );
+        assert_eq!(loaded.network.layout_method, LayoutMethod::Mds);
+        assert_eq!(loaded.network.layout_seed, 42);
+        let saved = loaded.network.saved.unwrap();
+        assert_eq!(saved.network.edges.len(), 1);
+        assert_eq!(saved.positions, [(1.0, 2.0), (3.0, 4.0)]);
+        assert_eq!(saved.pinned, [true, false]);
+    }
+
+    #[test]
+    fn newer_or_foreign_files_are_rejected() {
+        let mut value = Project::default().to_json();
+        assert!(Project::from_json(&value).is_ok());
+        value["version"] = json!(PROJECT_FILE_VERSION + 1);
+        assert!(Project::from_json(&value).is_err());
+
+        let value = json!({ "format": "jp_word_segment.network", "version": 1 });
+        assert!(Project::from_json(&value).is_err());
+    }
+}
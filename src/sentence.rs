@@ -0,0 +1,216 @@
+//! 括弧の入れ子を考慮した文分割
+
+use crate::escape_csv_field;
+use std::fs::File;
+use std::io::Write;
+
+/// 文分割の設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceSplitConfig {
+    pub terminators: Vec<char>,      // 文末記号 (連続する場合はまとめて 1 つの文末とする)
+    pub brackets: Vec<(char, char)>, // 内側では文を区切らない括弧 (開き, 閉じ)
+    pub keep_terminators: bool,      // 文末記号を文に含める
+    pub ignore_line_wraps: bool,     // 改行で区切らない (空行でのみ区切る)
+}
+
+impl Default for SentenceSplitConfig {
+    fn default() -> Self {
+        Self {
+            terminators: vec!['。', '．', '!', '！', '?', '？'],
+            brackets: vec![('「', '」'), ('『', '』'), ('（', '）'), ('(', ')')],
+            keep_terminators: false,
+            ignore_line_wraps: false,
+        }
+    }
+}
+
+/// 分割した文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceSpan {
+    pub start: usize,      // 元のテキストでの開始位置 (バイト)
+    pub end: usize,        // 元のテキストでの終了位置 (バイト, 含まない)
+    pub char_start: usize, // 開始位置 (文字数)
+    pub char_end: usize,   // 終了位置 (文字数, 含まない)
+    pub line: usize,       // 文が始まる行番号 (1 始まり)
+    pub text: String,      // 文 (改行で区切らない場合は折り返しの改行を除く)
+}
+
+/// テキストを文に分割し、元のテキスト上の位置とともに返す
+///
+/// 括弧の内側にある文末記号・改行では区切らない。閉じられていない括弧は空行
+/// (改行で区切る場合は改行) で打ち切る。前後の空白は文に含めない。
+pub fn split_sentence_spans(text: &str, config: &SentenceSplitConfig) -> Vec<SentenceSpan> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map(|c| c.0).unwrap_or(text.len());
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    let mut closers: Vec<char> = Vec::new(); // 開いている括弧に対応する閉じ括弧
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i].1;
+        if ch == '\n' || ch == '\r' {
+            // 改行と空白の連続をまとめ、改行が 2 つ以上なら空行とみなす
+            let mut j = i;
+            let mut newlines = 0;
+            while j < chars.len() && chars[j].1.is_whitespace() {
+                let c = chars[j].1;
+                if c == '\n' || (c == '\r' && chars.get(j + 1).map(|n| n.1) != Some('\n')) {
+                    newlines += 1;
+                }
+                j += 1;
+            }
+            if newlines >= 2 || !config.ignore_line_wraps {
+                ranges.push((start, chars[i].0));
+                start = byte_at(j);
+                closers.clear();
+            }
+            i = j;
+            continue;
+        }
+
+        if closers.last() == Some(&ch) {
+            closers.pop();
+        } else if let Some(&(_, close)) = config.brackets.iter().find(|(open, _)| *open == ch) {
+            closers.push(close);
+        } else if let Some(k) = closers.iter().rposition(|&c| c == ch) {
+            // 内側の括弧が閉じられていない場合は外側の括弧に合わせる
+            closers.truncate(k);
+        } else if closers.is_empty() && config.terminators.contains(&ch) {
+            let mut j = i + 1;
+            while j < chars.len() && config.terminators.contains(&chars[j].1) {
+                j += 1;
+            }
+            let end = if config.keep_terminators {
+                byte_at(j)
+            } else {
+                chars[i].0
+            };
+            ranges.push((start, end));
+            start = byte_at(j);
+            i = j;
+            continue;
+        }
+        i += 1;
+    }
+    ranges.push((start, text.len()));
+
+    // 前後の空白を除き、行番号・文字位置を付ける
+    let mut spans = Vec::new();
+    let (mut line, mut char_pos, mut scanned) = (1, 0, 0);
+    for (start, end) in ranges {
+        let slice = &text[start..end];
+        let trimmed = slice.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let start = start + (slice.len() - slice.trim_start().len());
+        let end = start + trimmed.len();
+        let skipped = &text[scanned..start];
+        line += skipped.matches('\n').count();
+        char_pos += skipped.chars().count();
+        let char_len = trimmed.chars().count();
+        let sentence = if config.ignore_line_wraps {
+            trimmed.chars().filter(|&c| c != '\n' && c != '\r').collect()
+        } else {
+            trimmed.to_string()
+        };
+        spans.push(SentenceSpan {
+            start,
+            end,
+            char_start: char_pos,
+            char_end: char_pos + char_len,
+            line,
+            text: sentence,
+        });
+        line += trimmed.matches('\n').count();
+        char_pos += char_len;
+        scanned = end;
+    }
+    spans
+}
+
+/// テキストを既定の設定で文に分割する (文末記号は含めない)
+pub fn split_sentences(text: &str) -> Vec<String> {
+    split_sentence_spans(text, &SentenceSplitConfig::default())
+        .into_iter()
+        .map(|span| span.text)
+        .collect()
+}
+
+/// 分割した文を CSV ファイルに保存 (UTF-8 BOM 付き、位置は文字数)
+pub fn save_sentences_to_csv(
+    spans: &[SentenceSpan],
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    writeln!(file, "番号,行,開始位置,終了位置,文")?;
+    for (i, span) in spans.iter().enumerate() {
+        writeln!(
+            file,
+            "{},{},{},{},{}",
+            i + 1,
+            span.line,
+            span.char_start,
+            span.char_end,
+            escape_csv_field(&span.text)
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(spans: &[SentenceSpan]) -> Vec<&str> {
+        spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn does_not_split_inside_quotes() {
+        let text = "彼は「行こう。すぐに！」と言った。次の文。";
+        assert_eq!(
+            split_sentences(text),
+            vec!["彼は「行こう。すぐに！」と言った", "次の文"]
+        );
+
+        let config = SentenceSplitConfig {
+            keep_terminators: true,
+            ..Default::default()
+        };
+        let spans = split_sentence_spans(text, &config);
+        assert_eq!(
+            texts(&spans),
+            vec!["彼は「行こう。すぐに！」と言った。", "次の文。"]
+        );
+        assert_eq!((spans[1].char_start, spans[1].char_end), (17, 21));
+        assert_eq!(&text[spans[1].start..spans[1].end], "次の文。");
+    }
+
+    #[test]
+    fn unclosed_quote_ends_at_line_break() {
+        let text = "「閉じない。\n次の行。";
+        let spans = split_sentence_spans(text, &SentenceSplitConfig::default());
+        assert_eq!(texts(&spans), vec!["「閉じない。", "次の行"]);
+        assert_eq!(spans[1].line, 2);
+    }
+
+    #[test]
+    fn ignores_line_wraps_until_blank_line() {
+        let config = SentenceSplitConfig {
+            ignore_line_wraps: true,
+            ..Default::default()
+        };
+        let text = "折り\n返した文。\n\n「括弧\n\n次の段落";
+        let spans = split_sentence_spans(text, &config);
+        assert_eq!(texts(&spans), vec!["折り返した文", "「括弧", "次の段落"]);
+        assert_eq!(
+            spans.iter().map(|s| s.line).collect::<Vec<_>>(),
+            vec![1, 4, 6]
+        );
+    }
+}
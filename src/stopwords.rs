@@ -0,0 +1,123 @@
+//! ストップワード (集計から除外する語) のリスト
+
+use crate::TokenizerCore;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+
+/// 同梱のストップワードリスト
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StopwordPreset {
+    #[default]
+    General, // 一般的な語 (SlothLib 風)
+    Function, // 助詞・助動詞・記号など
+}
+
+impl StopwordPreset {
+    pub const ALL: [StopwordPreset; 2] = [StopwordPreset::General, StopwordPreset::Function];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StopwordPreset::General => "一般的な語 (SlothLib 風)",
+            StopwordPreset::Function => "助詞・助動詞・記号",
+        }
+    }
+
+    /// リストに含まれる語
+    pub fn words(&self) -> HashSet<String> {
+        let text = match self {
+            StopwordPreset::General => include_str!("../assets/stopwords/general.txt"),
+            StopwordPreset::Function => include_str!("../assets/stopwords/function.txt"),
+        };
+        parse_stopwords(text)
+    }
+}
+
+/// ストップワードリストの文字列を解析する
+///
+/// 1 行に 1 語を基本とし、カンマ・空白で区切った複数語も受け付ける (読点は語として扱う)。
+/// "#" から行末まではコメントとして無視する。
+pub fn parse_stopwords(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+        .map(|word| word.trim_start_matches('\u{feff}').trim())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// ストップワードリストをファイルから読み込む
+pub fn load_stopwords(path: std::path::PathBuf) -> Result<HashSet<String>, std::io::Error> {
+    let mut text = String::new();
+    File::open(path)?.read_to_string(&mut text)?;
+    Ok(parse_stopwords(&text))
+}
+
+/// ストップワードリストをファイルに保存 (1 行に 1 語)
+pub fn save_stopwords(
+    words: &HashSet<String>,
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut sorted: Vec<&String> = words.iter().collect();
+    sorted.sort();
+    let mut file = File::create(path)?;
+    for word in sorted {
+        writeln!(file, "{}", word)?;
+    }
+    Ok(())
+}
+
+impl TokenizerCore {
+    /// ストップワードか
+    pub fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords.contains(word)
+    }
+
+    /// ストップワードを設定し、単語頻度を数え直す
+    pub fn set_stopwords(&mut self, words: HashSet<String>) {
+        self.stopwords = words;
+        self.recount_frequencies();
+    }
+
+    /// 解析済みのトークンからストップワードを除いて単語頻度を数え直す (`revision` も進める)
+    pub(crate) fn recount_frequencies(&mut self) {
+        let mut frequencies = std::collections::HashMap::new();
+        for info in &self.tokens {
+            if !self.stopwords.contains(&info.text) {
+                *frequencies.entry(info.text.clone()).or_insert(0) += 1;
+            }
+        }
+        self.word_frequencies = frequencies;
+        self.revision += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn stopwords_are_split_on_lines_commas_and_spaces() {
+        let text =
+            "\u{feff}これ\nそれ,あれ どれ\u{3000}ここ\n\n、\n# コメント\nもの # 行末のコメント\n";
+        assert_eq!(
+            parse_stopwords(text),
+            words(&["これ", "それ", "あれ", "どれ", "ここ", "、", "もの"])
+        );
+        assert!(parse_stopwords("# のみ\n \n").is_empty());
+    }
+
+    #[test]
+    fn presets_are_parsed() {
+        for preset in StopwordPreset::ALL {
+            let words = preset.words();
+            assert!(!words.is_empty(), "{:?}", preset);
+            assert!(words.iter().all(|w| !w.contains('#') && w.trim() == w));
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! 文書構造 (章・節・段落) の解析
+
+use crate::{escape_csv_field, TokenizerCore};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+
+/// 見出しを検出する正規表現 (前後の空白を除いた行に対して検索する)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureConfig {
+    pub chapter_patterns: Vec<String>, // 章見出し
+    pub section_patterns: Vec<String>, // 節見出し
+}
+
+impl Default for StructureConfig {
+    fn default() -> Self {
+        Self {
+            chapter_patterns: vec![
+                r"^第[0-9０-９一二三四五六七八九十百千]+[章部]".to_string(),
+                r"^(?i:chapter)\s*[0-9]+".to_string(),
+                r"^#\s".to_string(),
+            ],
+            section_patterns: vec![
+                r"^第[0-9０-９一二三四五六七八九十百千]+節".to_string(),
+                r"^##\s".to_string(),
+            ],
+        }
+    }
+}
+
+impl StructureConfig {
+    /// 正規表現をコンパイルする
+    fn compile(&self) -> Result<(Vec<Regex>, Vec<Regex>), regex::Error> {
+        let compile = |patterns: &[String]| -> Result<Vec<Regex>, regex::Error> {
+            patterns
+                .iter()
+                .filter(|p| !p.trim().is_empty())
+                .map(|p| Regex::new(p))
+                .collect()
+        };
+        Ok((
+            compile(&self.chapter_patterns)?,
+            compile(&self.section_patterns)?,
+        ))
+    }
+}
+
+/// 見出しの階層
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadingLevel {
+    Chapter,
+    Section,
+}
+
+impl HeadingLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeadingLevel::Chapter => "章",
+            HeadingLevel::Section => "節",
+        }
+    }
+}
+
+/// 見出し
+#[derive(Debug, Clone)]
+pub struct Heading {
+    pub level: HeadingLevel,
+    pub title: String,
+    pub line: usize, // 行番号 (1 始まり)
+}
+
+/// 段落 (空行・見出しで区切られた行のまとまり)
+#[derive(Debug, Clone)]
+pub struct Paragraph {
+    pub start_line: usize,      // 最初の行 (1 始まり)
+    pub end_line: usize,        // 最後の行 (含む)
+    pub chapter: Option<usize>, // 属する章見出し (`headings` の添字)
+    pub section: Option<usize>, // 属する節見出し (`headings` の添字)
+}
+
+/// 集計・比較に使う構造の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StructureUnit {
+    #[default]
+    Chapter,
+    Section,
+    Paragraph,
+}
+
+impl StructureUnit {
+    pub const ALL: [StructureUnit; 3] = [
+        StructureUnit::Chapter,
+        StructureUnit::Section,
+        StructureUnit::Paragraph,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StructureUnit::Chapter => "章",
+            StructureUnit::Section => "節",
+            StructureUnit::Paragraph => "段落",
+        }
+    }
+}
+
+/// 構造の単位 1 つ分 (連続する段落のまとまり)
+#[derive(Debug, Clone)]
+pub struct TextUnit {
+    pub label: String,
+    pub start_line: usize,      // 最初の段落の最初の行
+    pub end_line: usize,        // 最後の段落の最後の行 (含む)
+    pub paragraphs: Vec<usize>, // `DocumentStructure::paragraphs` の添字
+}
+
+/// 構造の単位による絞り込み (共起ネットワークの対象範囲など)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitScope {
+    pub unit: StructureUnit,
+    pub label: String,
+}
+
+/// 文書構造
+#[derive(Debug, Clone, Default)]
+pub struct DocumentStructure {
+    pub headings: Vec<Heading>,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+impl DocumentStructure {
+    /// テキストの構造を解析する
+    pub fn parse(text: &str, config: &StructureConfig) -> Result<Self, regex::Error> {
+        let (chapter_patterns, section_patterns) = config.compile()?;
+        let mut structure = DocumentStructure::default();
+        let mut chapter = None;
+        let mut section = None;
+        let mut current: Option<Paragraph> = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let trimmed = line.trim();
+            let level = if chapter_patterns.iter().any(|re| re.is_match(trimmed)) {
+                Some(HeadingLevel::Chapter)
+            } else if section_patterns.iter().any(|re| re.is_match(trimmed)) {
+                Some(HeadingLevel::Section)
+            } else {
+                None
+            };
+
+            if trimmed.is_empty() || level.is_some() {
+                structure.paragraphs.extend(current.take());
+            }
+            if let Some(level) = level {
+                let index = structure.headings.len();
+                structure.headings.push(Heading {
+                    level,
+                    title: trimmed.to_string(),
+                    line: line_number,
+                });
+                match level {
+                    HeadingLevel::Chapter => {
+                        chapter = Some(index);
+                        section = None;
+                    }
+                    HeadingLevel::Section => section = Some(index),
+                }
+                continue;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            match &mut current {
+                Some(paragraph) => paragraph.end_line = line_number,
+                None => {
+                    current = Some(Paragraph {
+                        start_line: line_number,
+                        end_line: line_number,
+                        chapter,
+                        section,
+                    })
+                }
+            }
+        }
+        structure.paragraphs.extend(current);
+        Ok(structure)
+    }
+
+    /// 指定した単位ごとに段落をまとめる (文書の順)
+    pub fn units(&self, unit: StructureUnit) -> Vec<TextUnit> {
+        let mut units: Vec<TextUnit> = Vec::new();
+        let mut last_key = None;
+        for (i, paragraph) in self.paragraphs.iter().enumerate() {
+            let key = match unit {
+                StructureUnit::Chapter => (paragraph.chapter, None, None),
+                StructureUnit::Section => (paragraph.chapter, paragraph.section, None),
+                StructureUnit::Paragraph => (None, None, Some(i)),
+            };
+            if last_key != Some(key) {
+                units.push(TextUnit {
+                    label: self.unit_label(unit, paragraph, i),
+                    start_line: paragraph.start_line,
+                    end_line: paragraph.end_line,
+                    paragraphs: Vec::new(),
+                });
+                last_key = Some(key);
+            }
+            if let Some(current) = units.last_mut() {
+                current.end_line = paragraph.end_line;
+                current.paragraphs.push(i);
+            }
+        }
+        units
+    }
+
+    fn unit_label(&self, unit: StructureUnit, paragraph: &Paragraph, index: usize) -> String {
+        let title = |heading: Option<usize>| heading.map(|h| self.headings[h].title.clone());
+        match unit {
+            StructureUnit::Chapter => {
+                title(paragraph.chapter).unwrap_or_else(|| "(見出しなし)".to_string())
+            }
+            StructureUnit::Section => match (title(paragraph.chapter), title(paragraph.section)) {
+                (Some(chapter), Some(section)) => format!("{} / {}", chapter, section),
+                (None, Some(section)) => section,
+                (Some(chapter), None) => chapter,
+                (None, None) => "(見出しなし)".to_string(),
+            },
+            StructureUnit::Paragraph => {
+                format!("段落 {} (行 {})", index + 1, paragraph.start_line)
+            }
+        }
+    }
+
+    /// 単位の本文 (見出しを除き、段落の間は空行で区切る)
+    pub fn unit_text(&self, text: &str, unit: &TextUnit) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        unit.paragraphs
+            .iter()
+            .filter_map(|&i| {
+                let paragraph = self.paragraphs.get(i)?;
+                let start = paragraph.start_line.saturating_sub(1).min(lines.len());
+                let end = paragraph.end_line.min(lines.len());
+                Some(lines[start..end].join("\n"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// 行番号を含む単位の添字
+pub fn find_unit(units: &[TextUnit], line: usize) -> Option<usize> {
+    units
+        .iter()
+        .position(|u| u.start_line <= line && line <= u.end_line)
+}
+
+impl TokenizerCore {
+    /// 見出しの検出パターンを設定して構造を解析し直す (不正な正規表現なら変更しない)
+    pub fn set_structure_config(&mut self, config: StructureConfig) -> Result<(), regex::Error> {
+        self.structure = DocumentStructure::parse(&self.input_text, &config)?;
+        self.structure_config = config;
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// 構造の単位の本文 (`scope` がなければテキスト全体)
+    ///
+    /// 該当する単位が複数あればすべてを空行で連結する。
+    pub fn scoped_text(&self, scope: Option<&UnitScope>) -> String {
+        let Some(scope) = scope else {
+            return self.input_text.clone();
+        };
+        self.structure
+            .units(scope.unit)
+            .iter()
+            .filter(|u| u.label == scope.label)
+            .map(|u| self.structure.unit_text(&self.input_text, u))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// 単位ごとの単語頻度 (ストップワードを除く)
+    pub fn unit_frequencies(&self, units: &[TextUnit]) -> Vec<HashMap<String, usize>> {
+        units
+            .iter()
+            .map(|u| self.count_frequencies(&self.structure.unit_text(&self.input_text, u)))
+            .collect()
+    }
+}
+
+/// 単位ごとの単語頻度の比較表を CSV ファイルに保存 (UTF-8 BOM 付き、合計の降順)
+pub fn save_unit_frequencies_to_csv(
+    units: &[TextUnit],
+    frequencies: &[HashMap<String, usize>],
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for counts in frequencies {
+        for (word, count) in counts {
+            *totals.entry(word.as_str()).or_insert(0) += count;
+        }
+    }
+    let mut words: Vec<(&str, usize)> = totals.into_iter().collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    let header: Vec<String> = units.iter().map(|u| escape_csv_field(&u.label)).collect();
+    writeln!(file, "単語,合計,{}", header.join(","))?;
+    for (word, total) in words {
+        let counts: Vec<String> = frequencies
+            .iter()
+            .map(|f| f.get(word).copied().unwrap_or(0).to_string())
+            .collect();
+        writeln!(
+            file,
+            "{},{},{}",
+            escape_csv_field(word),
+            total,
+            counts.join(",")
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str =
+        "まえがき\n\n第1章 はじめに\n本文A\n本文A続き\n\n第1節 背景\n本文B\n第2章 方法\n本文C";
+
+    fn labels(units: &[TextUnit]) -> Vec<&str> {
+        units.iter().map(|u| u.label.as_str()).collect()
+    }
+
+    #[test]
+    fn text_before_the_first_heading_has_no_heading() {
+        let structure = DocumentStructure::parse(TEXT, &StructureConfig::default()).unwrap();
+        let first = &structure.paragraphs[0];
+        assert_eq!((first.start_line, first.end_line), (1, 1));
+        assert_eq!((first.chapter, first.section), (None, None));
+
+        let units = structure.units(StructureUnit::Chapter);
+        assert_eq!(
+            labels(&units),
+            ["(見出しなし)", "第1章 はじめに", "第2章 方法"]
+        );
+        assert_eq!(structure.unit_text(TEXT, &units[0]), "まえがき");
+    }
+
+    #[test]
+    fn sections_are_nested_in_chapters() {
+        let structure = DocumentStructure::parse(TEXT, &StructureConfig::default()).unwrap();
+        let levels: Vec<_> = structure
+            .headings
+            .iter()
+            .map(|h| (h.level, h.line))
+            .collect();
+        assert_eq!(
+            levels,
+            [
+                (HeadingLevel::Chapter, 3),
+                (HeadingLevel::Section, 7),
+                (HeadingLevel::Chapter, 9),
+            ]
+        );
+
+        let chapters = structure.units(StructureUnit::Chapter);
+        assert_eq!(chapters[1].paragraphs, [1, 2]);
+        assert_eq!((chapters[1].start_line, chapters[1].end_line), (4, 8));
+        assert_eq!(
+            structure.unit_text(TEXT, &chapters[1]),
+            "本文A\n本文A続き\n\n本文B"
+        );
+
+        // 次の章に入ると節はなくなる
+        let sections = structure.units(StructureUnit::Section);
+        assert_eq!(
+            labels(&sections),
+            [
+                "(見出しなし)",
+                "第1章 はじめに",
+                "第1章 はじめに / 第1節 背景",
+                "第2章 方法",
+            ]
+        );
+        assert_eq!(structure.units(StructureUnit::Paragraph).len(), 4);
+        assert_eq!(find_unit(&sections, 8), Some(2));
+        assert_eq!(find_unit(&sections, 2), None);
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        let config = StructureConfig {
+            chapter_patterns: vec!["^第(".to_string()],
+            ..StructureConfig::default()
+        };
+        assert!(DocumentStructure::parse(TEXT, &config).is_err());
+
+        // 空のパターンは無視する
+        let config = StructureConfig {
+            chapter_patterns: vec![" ".to_string()],
+            section_patterns: Vec::new(),
+        };
+        let structure = DocumentStructure::parse(TEXT, &config).unwrap();
+        assert!(structure.headings.is_empty());
+        assert_eq!(structure.units(StructureUnit::Chapter).len(), 1);
+    }
+}
@@ -0,0 +1,426 @@
+//! 未知語 (辞書にない語) の一覧とユーザー辞書に登録する語の候補の抽出
+
+use crate::{escape_csv_field, ConcordanceResult, TokenInfo, TokenizerCore, UserWord};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+
+/// 未知語の一覧の 1 行
+#[derive(Debug, Clone)]
+pub struct UnknownWord {
+    pub word: String,
+    pub pos: String, // 未知語処理で推定した品詞 (細分類まで "-" で連結)
+    pub frequency: usize,
+    pub contexts: Vec<ConcordanceResult>, // 出現箇所の文脈 (先頭から最大 `max_contexts` 件)
+}
+
+/// 辞書登録候補の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandidateKind {
+    Unknown,      // 未知語
+    Katakana,     // 複数の語に分かれたカタカナの連続
+    NounSequence, // 名詞の連続 (複合名詞)
+}
+
+impl CandidateKind {
+    pub const ALL: [CandidateKind; 3] = [
+        CandidateKind::Unknown,
+        CandidateKind::Katakana,
+        CandidateKind::NounSequence,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CandidateKind::Unknown => "未知語",
+            CandidateKind::Katakana => "カタカナ連続",
+            CandidateKind::NounSequence => "名詞連続",
+        }
+    }
+}
+
+/// ユーザー辞書に登録する語の候補
+#[derive(Debug, Clone)]
+pub struct DictionaryCandidate {
+    pub surface: String,
+    pub kind: CandidateKind,
+    pub pos: String,        // 登録する品詞
+    pub reading: String,    // 構成語の読みを連結したもの (読みのない語を含めば空)
+    pub parts: Vec<String>, // 現在の分割 (構成語)
+    pub frequency: usize,
+}
+
+impl DictionaryCandidate {
+    /// ユーザー辞書に登録する語
+    pub fn to_user_word(&self) -> UserWord {
+        UserWord {
+            surface: self.surface.clone(),
+            pos: self.pos.clone(),
+            reading: self.reading.clone(),
+            cost: None,
+        }
+    }
+}
+
+/// 候補抽出の条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateConfig {
+    pub min_frequency: usize,
+    pub max_parts: usize, // 連続として扱う最大の語数
+    pub katakana: bool,   // カタカナの連続を候補にする
+    pub nouns: bool,      // 名詞の連続を候補にする
+}
+
+impl Default for CandidateConfig {
+    fn default() -> Self {
+        Self {
+            min_frequency: 2,
+            max_parts: 6,
+            katakana: true,
+            nouns: true,
+        }
+    }
+}
+
+/// カタカナ (長音符・中黒を含む) だけからなる語か
+fn is_katakana_word(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| matches!(c, '\u{30A1}'..='\u{30FA}' | 'ー' | '・' | 'ヽ' | 'ヾ'))
+}
+
+/// 複合名詞を構成する名詞か (非自立・代名詞・数・接尾などは除く)
+fn is_compound_noun_part(token: &TokenInfo) -> bool {
+    const EXCLUDED: [&str; 6] = ["非自立", "代名詞", "数", "接尾", "特殊", "副詞可能"];
+    token.pos == "名詞"
+        && !token
+            .pos_detail
+            .first()
+            .map(|d| EXCLUDED.contains(&d.as_str()))
+            .unwrap_or(false)
+}
+
+/// `part` を満たし、間を空けずに隣り合う 2 語以上の最長の連続 (トークンの添字の範囲)
+fn contiguous_runs(
+    tokens: &[TokenInfo],
+    part: impl Fn(&TokenInfo) -> bool,
+) -> Vec<std::ops::Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let continues = start.is_some() && part(token) && tokens[i - 1].end == token.start;
+        if !continues {
+            if let Some(start) = start {
+                runs.push(start..i);
+            }
+            start = part(token).then_some(i);
+        }
+    }
+    if let Some(start) = start {
+        runs.push(start..tokens.len());
+    }
+    runs.retain(|run| run.len() >= 2);
+    runs
+}
+
+/// トークン列からユーザー辞書に登録する語の候補を集める (頻度の降順)
+///
+/// 未知語、複数の語に分かれたカタカナの連続、名詞の連続を候補とする。
+/// 連続は間に空白などを挟まずに隣り合う語だけをまとめ、最長の連続を 1 つの候補とする。
+fn collect_candidates(tokens: &[TokenInfo], config: &CandidateConfig) -> Vec<DictionaryCandidate> {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut candidates: Vec<DictionaryCandidate> = Vec::new();
+    let mut add = |tokens: &[TokenInfo], kind: CandidateKind| {
+        let surface: String = tokens.iter().map(|t| t.text.as_str()).collect();
+        match index.get(&surface) {
+            Some(&i) => candidates[i].frequency += 1,
+            None => {
+                let reading = if is_katakana_word(&surface) {
+                    surface.clone()
+                } else if tokens.iter().all(|t| !t.reading.is_empty()) {
+                    tokens.iter().map(|t| t.reading.as_str()).collect()
+                } else {
+                    String::new()
+                };
+                // 複合名詞の品詞は末尾の語 (サ変接続なら「〜する」が続く) に合わせる
+                let last = tokens.last().unwrap();
+                let pos = if kind == CandidateKind::Unknown && tokens.len() == 1 {
+                    last.pos_path()
+                } else if last.pos_detail.first().map(|d| d.as_str()) == Some("サ変接続") {
+                    "名詞-サ変接続".to_string()
+                } else {
+                    "名詞-一般".to_string()
+                };
+                index.insert(surface.clone(), candidates.len());
+                candidates.push(DictionaryCandidate {
+                    surface,
+                    kind,
+                    pos,
+                    reading,
+                    parts: tokens.iter().map(|t| t.text.clone()).collect(),
+                    frequency: 1,
+                });
+            }
+        }
+    };
+
+    for token in tokens
+        .iter()
+        .filter(|t| t.unknown && !t.text.trim().is_empty())
+    {
+        add(std::slice::from_ref(token), CandidateKind::Unknown);
+    }
+    if config.katakana {
+        for run in contiguous_runs(tokens, |t| is_katakana_word(&t.text)) {
+            if run.len() <= config.max_parts {
+                add(&tokens[run], CandidateKind::Katakana);
+            }
+        }
+    }
+    if config.nouns {
+        for run in contiguous_runs(tokens, is_compound_noun_part) {
+            // カタカナだけの連続はカタカナ連続として数える
+            let run = &tokens[run];
+            let katakana = config.katakana && run.iter().all(|t| is_katakana_word(&t.text));
+            if run.len() <= config.max_parts && !katakana {
+                add(run, CandidateKind::NounSequence);
+            }
+        }
+    }
+
+    candidates.retain(|c| c.frequency >= config.min_frequency);
+    candidates.sort_by(|a, b| {
+        b.frequency
+            .cmp(&a.frequency)
+            .then_with(|| a.surface.cmp(&b.surface))
+    });
+    candidates
+}
+
+/// 行の先頭位置 (バイト) の一覧
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+impl TokenizerCore {
+    /// トークンの前後の文脈 (同じ行の前後 `context_size` 語まで)
+    fn token_context(
+        &self,
+        index: usize,
+        context_size: usize,
+        lines: &[usize],
+    ) -> ConcordanceResult {
+        let token = &self.tokens[index];
+        let line = lines.partition_point(|&start| start <= token.start) - 1;
+        let line_start = lines[line];
+        let line_end = lines
+            .get(line + 1)
+            .map(|&next| next - 1)
+            .unwrap_or(self.input_text.len());
+        let left_start = self.tokens[index.saturating_sub(context_size)..index]
+            .first()
+            .map(|t| t.start.max(line_start))
+            .unwrap_or(token.start);
+        let right = &self.tokens[index + 1..];
+        let right_end = right[..context_size.min(right.len())]
+            .last()
+            .map(|t| t.end.min(line_end).max(token.end))
+            .unwrap_or(token.end);
+        ConcordanceResult {
+            keyword: token.text.clone(),
+            left_context: self.input_text[left_start..token.start].trim().to_string(),
+            right_context: self.input_text[token.end..right_end].trim().to_string(),
+            line_number: line + 1,
+        }
+    }
+
+    /// 未知語の一覧 (頻度の降順)
+    pub fn unknown_words(&self, context_size: usize, max_contexts: usize) -> Vec<UnknownWord> {
+        let lines = line_starts(&self.input_text);
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut words: Vec<UnknownWord> = Vec::new();
+        for (i, token) in self.tokens.iter().enumerate() {
+            if !token.unknown || token.text.trim().is_empty() {
+                continue;
+            }
+            let entry = match index.get(token.text.as_str()) {
+                Some(&j) => &mut words[j],
+                None => {
+                    index.insert(token.text.as_str(), words.len());
+                    words.push(UnknownWord {
+                        word: token.text.clone(),
+                        pos: token.pos_path(),
+                        frequency: 0,
+                        contexts: Vec::new(),
+                    });
+                    words.last_mut().unwrap()
+                }
+            };
+            entry.frequency += 1;
+            if entry.contexts.len() < max_contexts {
+                entry
+                    .contexts
+                    .push(self.token_context(i, context_size, &lines));
+            }
+        }
+        words.sort_by(|a, b| {
+            b.frequency
+                .cmp(&a.frequency)
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        words
+    }
+
+    /// ユーザー辞書に登録する語の候補 (頻度の降順)
+    pub fn dictionary_candidates(&self, config: &CandidateConfig) -> Vec<DictionaryCandidate> {
+        collect_candidates(&self.tokens, config)
+    }
+}
+
+/// 未知語の一覧をCSVファイルに保存する (文脈は最初の出現箇所)
+pub fn save_unknown_words_to_csv(
+    words: &[UnknownWord],
+    path: std::path::PathBuf,
+) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+
+    // UTF-8 BOMを書き込み（Excel対応）
+    file.write_all(&[0xEF, 0xBB, 0xBF])?;
+
+    writeln!(file, "単語,推定品詞,出現頻度,行番号,左文脈,右文脈")?;
+    for word in words {
+        let context = word.contexts.first();
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            escape_csv_field(&word.word),
+            escape_csv_field(&word.pos),
+            word.frequency,
+            context
+                .map(|c| c.line_number.to_string())
+                .unwrap_or_default(),
+            escape_csv_field(context.map(|c| c.left_context.as_str()).unwrap_or("")),
+            escape_csv_field(context.map(|c| c.right_context.as_str()).unwrap_or(""))
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens_from_features;
+
+    /// 「データベース管理、これモフモフ」(「モフ」は未知語)
+    fn tokens() -> Vec<TokenInfo> {
+        let mut tokens = tokens_from_features(&[
+            ("データ", "名詞,一般,*,*,*,*,データ,データ,データ"),
+            ("ベース", "名詞,一般,*,*,*,*,ベース,ベース,ベース"),
+            ("管理", "名詞,サ変接続,*,*,*,*,管理,カンリ,カンリ"),
+            ("、", "記号,読点,*,*,*,*,、,、,、"),
+            ("これ", "名詞,代名詞,一般,*,*,*,これ,コレ,コレ"),
+            ("モフ", "名詞,一般,*,*,*,*,*"),
+            ("モフ", "名詞,一般,*,*,*,*,*"),
+        ]);
+        tokens[5].unknown = true;
+        tokens[6].unknown = true;
+        tokens
+    }
+
+    #[test]
+    fn katakana_words_include_long_vowels_and_middle_dots() {
+        assert!(is_katakana_word("データ"));
+        assert!(is_katakana_word("ジョン・スミス"));
+        assert!(!is_katakana_word("データ化"));
+        assert!(!is_katakana_word("ﾃﾞｰﾀ"));
+        assert!(!is_katakana_word(""));
+    }
+
+    #[test]
+    fn runs_are_longest_adjacent_sequences() {
+        let mut tokens = tokens();
+        assert_eq!(
+            contiguous_runs(&tokens, is_compound_noun_part),
+            [0..3, 5..7]
+        );
+        assert_eq!(
+            contiguous_runs(&tokens, |t| is_katakana_word(&t.text)),
+            [0..2, 5..7]
+        );
+        // 間に空白などがあれば連続としない
+        tokens[1].start += 1;
+        assert_eq!(
+            contiguous_runs(&tokens, is_compound_noun_part),
+            [1..3, 5..7]
+        );
+    }
+
+    #[test]
+    fn candidates_are_collected_by_kind() {
+        let tokens = tokens();
+        let config = CandidateConfig {
+            min_frequency: 1,
+            ..Default::default()
+        };
+        let candidates = collect_candidates(&tokens, &config);
+        let summary: Vec<_> = candidates
+            .iter()
+            .map(|c| {
+                (
+                    c.surface.as_str(),
+                    c.kind,
+                    c.pos.as_str(),
+                    c.reading.as_str(),
+                    c.frequency,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("モフ", CandidateKind::Unknown, "名詞-一般", "モフ", 2),
+                (
+                    "データベース",
+                    CandidateKind::Katakana,
+                    "名詞-一般",
+                    "データベース",
+                    1
+                ),
+                (
+                    "データベース管理",
+                    CandidateKind::NounSequence,
+                    "名詞-サ変接続",
+                    "データベースカンリ",
+                    1
+                ),
+                (
+                    "モフモフ",
+                    CandidateKind::Katakana,
+                    "名詞-一般",
+                    "モフモフ",
+                    1
+                ),
+            ]
+        );
+        assert_eq!(candidates[2].parts, ["データ", "ベース", "管理"]);
+
+        let config = CandidateConfig {
+            max_parts: 2,
+            katakana: false,
+            ..config
+        };
+        let surfaces: Vec<String> = collect_candidates(&tokens, &config)
+            .into_iter()
+            .map(|c| c.surface)
+            .collect();
+        // カタカナ連続を候補にしなければ名詞連続として数える
+        assert_eq!(surfaces, ["モフ", "モフモフ"]);
+        assert_eq!(
+            collect_candidates(&tokens, &CandidateConfig::default()).len(),
+            1
+        );
+    }
+}
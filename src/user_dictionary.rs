@@ -0,0 +1,366 @@
+//! ユーザー辞書 (lindera の IPADIC 形式 CSV) の読み書き
+
+use crate::{escape_csv_field, split_csv_record, TokenInfo, TokenizerCore};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+
+/// 登録する語の生起コスト (lindera の簡易形式と同じ値。システム辞書の語より優先される)
+const USER_WORD_COST: i32 = -10000;
+
+/// ユーザー辞書に登録する語
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserWord {
+    pub surface: String,
+    pub pos: String,       // 品詞 (細分類まで "-" で連結。例: "名詞-固有名詞-一般")
+    pub reading: String,   // 読み (カタカナ、空なら "*")
+    pub cost: Option<i32>, // 生起コスト (None ならシステム辞書の語より優先する値)
+}
+
+impl UserWord {
+    /// ユーザー辞書の CSV の 1 行
+    ///
+    /// 品詞の細分類を保持するため詳細形式 (表層形,左文脈ID,右文脈ID,コスト,品詞,細分類1〜3,
+    /// 活用型,活用形,原形,読み,発音) で書き出す。
+    pub fn to_csv_line(&self) -> String {
+        let mut pos: Vec<&str> = self
+            .pos
+            .split('-')
+            .filter(|p| !p.is_empty())
+            .take(4)
+            .collect();
+        pos.resize(4, "*");
+        let reading = if self.reading.is_empty() {
+            "*"
+        } else {
+            self.reading.as_str()
+        };
+        let cost = self.cost.unwrap_or(USER_WORD_COST).to_string();
+        let mut fields = vec![self.surface.as_str(), "0", "0", cost.as_str()];
+        fields.extend(pos);
+        fields.extend(["*", "*", self.surface.as_str(), reading, reading]);
+        fields
+            .iter()
+            .map(|f| escape_csv_field(f))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// CSV の 1 行から読む (簡易形式「表層形,品詞,読み」と詳細形式に対応)
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        let surface = fields.first()?.clone();
+        if surface.is_empty() {
+            return None;
+        }
+        let (pos, reading, cost) = if fields.len() >= 13 {
+            let pos: Vec<&str> = fields[4..8]
+                .iter()
+                .map(|f| f.as_str())
+                .filter(|f| *f != "*")
+                .collect();
+            (
+                pos.join("-"),
+                fields[11].clone(),
+                fields[3].trim().parse().ok(),
+            )
+        } else {
+            (
+                fields.get(1).cloned().unwrap_or_default(),
+                fields.get(2).cloned().unwrap_or_default(),
+                None,
+            )
+        };
+        Some(Self {
+            surface,
+            pos,
+            reading: if reading == "*" {
+                String::new()
+            } else {
+                reading
+            },
+            cost,
+        })
+    }
+}
+
+/// 分割の修正で区切った語の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrectionKind {
+    Merge,     // 複数の語をまとめた語 (システム辞書の語より優先して登録する)
+    Relabel,   // 元の語のまま品詞・読みを変えた語 (優先して登録する)
+    Split,     // 元の語を分割した語 (システム辞書にある語も優先して登録する)
+    Unchanged, // 元の語と同じ語 (登録しない)
+}
+
+impl CorrectionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CorrectionKind::Merge => "結合 (優先して登録)",
+            CorrectionKind::Relabel => "品詞・読みの修正 (優先して登録)",
+            CorrectionKind::Split => "分割 (優先して登録)",
+            CorrectionKind::Unchanged => "変更なし (登録しない)",
+        }
+    }
+
+    /// ユーザー辞書に登録する語 (登録しなければ None)
+    pub fn user_word(&self, word: &UserWord) -> Option<UserWord> {
+        match self {
+            CorrectionKind::Unchanged => None,
+            _ => Some(UserWord {
+                cost: None,
+                ..word.clone()
+            }),
+        }
+    }
+}
+
+/// 選択したトークンを `words` に区切り直す時の各語の扱いを決める
+///
+/// `words` の表層形を連結したものは `tokens` の表層形を連結したものと一致していること。
+/// 分割した語は、元の語より低いコストでなければ分割が反映されないため、システム辞書に
+/// ある語も含めて優先するコストで登録する (1 文字の語は他の箇所の分割も変えやすいので、
+/// 登録するかどうかは呼び出し側で確認する)。
+pub fn classify_correction(tokens: &[TokenInfo], words: &[UserWord]) -> Vec<CorrectionKind> {
+    // 選択範囲の先頭からのバイト位置でのトークンの境界
+    let mut bounds = Vec::with_capacity(tokens.len());
+    let mut offset = 0;
+    for token in tokens {
+        bounds.push((offset, offset + token.text.len()));
+        offset += token.text.len();
+    }
+
+    let mut start = 0;
+    words
+        .iter()
+        .map(|word| {
+            let end = start + word.surface.len();
+            let first = bounds.iter().position(|&(s, _)| s == start);
+            let last = bounds.iter().position(|&(_, e)| e == end);
+            let kind = match (first, last) {
+                (Some(first), Some(last)) if first < last => CorrectionKind::Merge,
+                (Some(i), Some(_)) => {
+                    let token = &tokens[i];
+                    if word.pos == token.pos_path() && word.reading == token.reading {
+                        CorrectionKind::Unchanged
+                    } else {
+                        CorrectionKind::Relabel
+                    }
+                }
+                _ => CorrectionKind::Split,
+            };
+            start = end;
+            kind
+        })
+        .collect()
+}
+
+/// ファイルの内容 (存在しなければ空)
+fn read_lines(path: &std::path::Path) -> Result<Vec<String>, std::io::Error> {
+    let mut text = String::new();
+    match File::open(path) {
+        Ok(mut file) => {
+            file.read_to_string(&mut text)?;
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    Ok(text
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// ユーザー辞書を読み込む
+pub fn load_user_words(path: std::path::PathBuf) -> Result<Vec<UserWord>, std::io::Error> {
+    Ok(read_lines(&path)?
+        .iter()
+        .filter_map(|line| UserWord::from_fields(&split_csv_record(line, ',')))
+        .collect())
+}
+
+/// 語の一覧をユーザー辞書として保存する (既存のファイルは上書き)
+pub fn save_user_words(words: &[UserWord], path: std::path::PathBuf) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+    for word in words {
+        writeln!(file, "{}", word.to_csv_line())?;
+    }
+    Ok(())
+}
+
+/// ユーザー辞書の語を置き換える
+///
+/// `remove` と `add` の表層形に一致する行を取り除き、`add` の語を末尾に追加する。
+/// その他の行は書式を変えずに残す。
+fn update_user_dictionary(
+    path: &std::path::Path,
+    remove: &[String],
+    add: &[UserWord],
+) -> Result<(), std::io::Error> {
+    let replaced: HashSet<&str> = remove
+        .iter()
+        .map(|s| s.as_str())
+        .chain(add.iter().map(|w| w.surface.as_str()))
+        .collect();
+    let mut lines: Vec<String> = read_lines(path)?
+        .into_iter()
+        .filter(|line| {
+            let fields = split_csv_record(line, ',');
+            !replaced.contains(fields[0].as_str())
+        })
+        .collect();
+    lines.extend(add.iter().map(|w| w.to_csv_line()));
+
+    let mut file = File::create(path)?;
+    for line in lines {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+impl TokenizerCore {
+    /// ユーザー辞書に語を登録し、形態素解析器を読み込み直して解析し直す
+    ///
+    /// 同じ表層形の語がすでにあれば置き換える。`path` を今後使うユーザー辞書として設定する。
+    pub fn register_user_words(
+        &mut self,
+        path: std::path::PathBuf,
+        words: &[UserWord],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        update_user_dictionary(&path, &[], words)?;
+        self.reload_user_dictionary(path)
+    }
+
+    /// ユーザー辞書から語を削除し、形態素解析器を読み込み直して解析し直す
+    pub fn remove_user_words(
+        &mut self,
+        path: std::path::PathBuf,
+        surfaces: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        update_user_dictionary(&path, surfaces, &[])?;
+        self.reload_user_dictionary(path)
+    }
+
+    /// ユーザー辞書を読み込み直す (語が 1 つもなければユーザー辞書を使わない)
+    fn reload_user_dictionary(
+        &mut self,
+        path: std::path::PathBuf,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut settings = self.settings.clone();
+        settings.user_dictionary = if read_lines(&path)?.is_empty() {
+            None
+        } else {
+            Some(path.to_string_lossy().into_owned())
+        };
+        self.set_tokenizer_settings(settings)?;
+        if !self.tokens.is_empty() {
+            self.analyze_text();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tokens_from_features, TokenizerSettings};
+
+    fn word(surface: &str, pos: &str, reading: &str) -> UserWord {
+        UserWord {
+            surface: surface.to_string(),
+            pos: pos.to_string(),
+            reading: reading.to_string(),
+            cost: None,
+        }
+    }
+
+    #[test]
+    fn merged_and_split_words_are_registered_before_system_words() {
+        let tokens = tokens_from_features(&[
+            ("情報", "名詞,一般,*,*,*,*,情報,ジョウホウ,ジョーホー"),
+            ("処理", "名詞,サ変接続,*,*,*,*,処理,ショリ,ショリ"),
+        ]);
+        let merged = [word("情報処理", "名詞-サ変接続", "ジョウホウショリ")];
+        let kinds = classify_correction(&tokens, &merged);
+        assert_eq!(kinds, vec![CorrectionKind::Merge]);
+        assert_eq!(kinds[0].user_word(&merged[0]).unwrap().cost, None);
+
+        let same = [
+            word("情報", "名詞-一般", "ジョウホウ"),
+            word("処理", "名詞-一般", "ショリ"),
+        ];
+        let kinds = classify_correction(&tokens, &same);
+        assert_eq!(
+            kinds,
+            vec![CorrectionKind::Unchanged, CorrectionKind::Relabel]
+        );
+        assert_eq!(kinds[0].user_word(&same[0]), None);
+
+        // 日本|語: システム辞書にある語も含めて、どちらも優先するコストで登録する
+        let tokens = tokens_from_features(&[(
+            "日本語",
+            "名詞,一般,*,*,*,*,日本語,ニホンゴ,ニホンゴ",
+        )]);
+        let split = [
+            word("日本", "名詞-固有名詞-地域-国", "ニホン"),
+            word("語", "名詞-接尾-一般", "ゴ"),
+        ];
+        let kinds = classify_correction(&tokens, &split);
+        assert_eq!(kinds, vec![CorrectionKind::Split, CorrectionKind::Split]);
+        for (kind, word) in kinds.iter().zip(&split) {
+            assert_eq!(kind.user_word(word).unwrap().cost, None);
+        }
+    }
+
+    /// 登録した分割が解析し直した結果に反映される (埋め込みの IPADIC が必要)
+    #[test]
+    fn registered_split_is_used_when_tokenizing_again() {
+        let surfaces = |settings: &TokenizerSettings| -> Vec<String> {
+            let tokenizer = settings.build().expect("IPADIC を読み込めません");
+            let mut tokens = tokenizer.tokenize("日本語を話す").unwrap();
+            tokens
+                .iter_mut()
+                .map(|token| TokenInfo::from_token(token).text)
+                .collect()
+        };
+        let mut settings = TokenizerSettings::default();
+        assert_eq!(surfaces(&settings), ["日本語", "を", "話す"]);
+
+        let tokens = tokens_from_features(&[(
+            "日本語",
+            "名詞,一般,*,*,*,*,日本語,ニホンゴ,ニホンゴ",
+        )]);
+        let split = [
+            word("日本", "名詞-固有名詞-地域-国", "ニホン"),
+            word("語", "名詞-接尾-一般", "ゴ"),
+        ];
+        let add: Vec<UserWord> = classify_correction(&tokens, &split)
+            .iter()
+            .zip(&split)
+            .filter_map(|(kind, word)| kind.user_word(word))
+            .collect();
+        let path = std::env::temp_dir().join("jp_word_segment_user_dictionary_test.csv");
+        update_user_dictionary(&path, &[], &add).unwrap();
+        settings.user_dictionary = Some(path.to_string_lossy().into_owned());
+        let result = surfaces(&settings);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result, ["日本", "語", "を", "話す"]);
+    }
+
+    #[test]
+    fn cost_is_written_and_read_back() {
+        let mut word = word("都", "名詞-接尾-地域", "ト");
+        assert_eq!(
+            word.to_csv_line(),
+            "都,0,0,-10000,名詞,接尾,地域,*,*,*,都,ト,ト"
+        );
+        word.cost = Some(2000);
+        let line = word.to_csv_line();
+        assert_eq!(line, "都,0,0,2000,名詞,接尾,地域,*,*,*,都,ト,ト");
+        assert_eq!(
+            UserWord::from_fields(&split_csv_record(&line, ',')),
+            Some(word)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use super::correction::CorrectionView;
+use eframe::egui;
+use eframe::egui::text::{LayoutJob, TextFormat};
+use jp_word_segment::{ipadic_pos_children, TokenInfo, TokenizerCore, IPADIC_FEATURES};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// 品詞ごとの色 (最上位の品詞の順)
+const POS_COLORS: [(u8, u8, u8); 13] = [
+    (66, 133, 244),  // 名詞
+    (219, 68, 55),   // 動詞
+    (244, 160, 0),   // 形容詞
+    (171, 71, 188),  // 副詞
+    (0, 172, 193),   // 連体詞
+    (124, 179, 66),  // 接続詞
+    (255, 112, 67),  // 感動詞
+    (92, 107, 192),  // 接頭詞
+    (158, 158, 158), // 助詞
+    (38, 166, 154),  // 助動詞
+    (189, 189, 189), // 記号
+    (141, 110, 99),  // フィラー
+    (120, 144, 156), // その他
+];
+
+/// 境界の区切りとして挟む文字
+const BOUNDARY_MARK: &str = "|";
+
+/// 描画する文字列の区間 (レイアウト上の文字位置)
+struct Section {
+    char_start: usize,
+    token: Option<usize>, // 対応するトークン (トークン以外の文字は None)
+}
+
+/// レイアウトを作った条件 (revision, テキストのハッシュ, 色分けする品詞, 境界, ダークモード, 選択)
+type BuildConditions = (
+    u64,
+    u64,
+    HashSet<String>,
+    bool,
+    bool,
+    Option<(usize, usize)>,
+);
+
+/// 解析後の編集を検出するためのテキストのハッシュ (同じ長さの書き換えも区別する)
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 解析結果を元のテキスト上に品詞で色分けして表示するビュー
+pub struct AnnotatedTextView {
+    highlighted: HashSet<String>, // 色分けする最上位の品詞
+    show_boundaries: bool,
+    job: Option<LayoutJob>,
+    sections: Vec<Section>,
+    selection: Option<(usize, usize)>, // 選択中のトークンの範囲 (先頭と末尾の添字)
+    selection_revision: u64,           // `selection` を選んだ TokenizerCore の revision
+    correction: CorrectionView,
+    built: Option<BuildConditions>,
+}
+
+impl Default for AnnotatedTextView {
+    fn default() -> Self {
+        Self {
+            highlighted: ipadic_pos_children(None).into_iter().collect(),
+            show_boundaries: true,
+            job: None,
+            sections: Vec::new(),
+            selection: None,
+            selection_revision: 0,
+            correction: CorrectionView::default(),
+            built: None,
+        }
+    }
+}
+
+/// 最上位の品詞の色
+fn pos_color(pos: &str, dark_mode: bool) -> egui::Color32 {
+    let index = ipadic_pos_children(None)
+        .iter()
+        .position(|p| p == pos)
+        .unwrap_or(POS_COLORS.len() - 1)
+        .min(POS_COLORS.len() - 1);
+    let (r, g, b) = POS_COLORS[index];
+    let alpha = if dark_mode { 110 } else { 70 };
+    egui::Color32::from_rgba_unmultiplied(r, g, b, alpha)
+}
+
+/// トークンの位置が解析したテキストと一致しているか (解析後にテキストを編集していないか)
+fn tokens_match_text(tokens: &[TokenInfo], text: &str) -> bool {
+    tokens
+        .iter()
+        .all(|t| text.get(t.start..t.end) == Some(t.text.as_str()))
+}
+
+impl AnnotatedTextView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        if core.tokens.is_empty() {
+            ui.label("「解析」ボタンを押すと解析結果がテキスト上に表示されます。");
+            return;
+        }
+        // 解析し直したらトークンの添字が変わるので選択を解除する
+        if self.selection_revision != core.revision {
+            self.selection = None;
+            self.selection_revision = core.revision;
+        }
+
+        let dark_mode = ui.visuals().dark_mode;
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.show_boundaries, "境界を表示");
+            ui.label("色分けする品詞:");
+            for pos in ipadic_pos_children(None) {
+                let mut checked = self.highlighted.contains(&pos);
+                let text =
+                    egui::RichText::new(pos.as_str()).background_color(pos_color(&pos, dark_mode));
+                if ui.checkbox(&mut checked, text).changed() {
+                    if checked {
+                        self.highlighted.insert(pos);
+                    } else {
+                        self.highlighted.remove(&pos);
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("すべて").clicked() {
+                self.highlighted = ipadic_pos_children(None).into_iter().collect();
+            }
+            if ui.button("解除").clicked() {
+                self.highlighted.clear();
+            }
+        });
+
+        let conditions = (
+            core.revision,
+            text_hash(&core.input_text),
+            self.highlighted.clone(),
+            self.show_boundaries,
+            dark_mode,
+            self.selection,
+        );
+        if self.built.as_ref() != Some(&conditions) {
+            self.build(ui, core, dark_mode);
+            self.built = Some(conditions);
+        }
+        let Some(job) = &self.job else {
+            ui.label("解析後にテキストが変更されています。「解析」ボタンで解析し直してください。");
+            return;
+        };
+        ui.label("トークンをクリックして選択 (Shift+クリックで範囲を広げる) すると、分割を修正できます。");
+
+        let mut clicked = None;
+        egui::ScrollArea::vertical()
+            .id_source("annotated_text_scroll")
+            .max_height(400.0)
+            .show(ui, |ui| {
+                let mut job = job.clone();
+                job.wrap.max_width = ui.available_width();
+                let galley = ui.fonts(|fonts| fonts.layout_job(job));
+                let (rect, response) = ui.allocate_exact_size(galley.size(), egui::Sense::click());
+                let token_at = |pos: egui::Pos2| {
+                    let cursor = galley.cursor_from_pos(pos - rect.min);
+                    let i = self
+                        .sections
+                        .partition_point(|s| s.char_start <= cursor.ccursor.index);
+                    self.sections.get(i.checked_sub(1)?)?.token
+                };
+                let hovered = response.hover_pos().and_then(token_at);
+                if response.clicked() {
+                    clicked = response.interact_pointer_pos().and_then(token_at);
+                }
+                ui.painter().galley(rect.min, galley);
+
+                if let Some(token) = hovered.and_then(|i| core.tokens.get(i)) {
+                    response.on_hover_ui_at_pointer(|ui| {
+                        egui::Grid::new("annotated_text_features").show(ui, |ui| {
+                            ui.label("表層形");
+                            ui.label(&token.text);
+                            ui.end_row();
+                            for (name, value) in IPADIC_FEATURES.iter().zip(&token.features) {
+                                ui.label(*name);
+                                ui.label(value);
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(i) = clicked {
+            let extend = ui.input(|input| input.modifiers.shift);
+            self.selection = match self.selection {
+                Some((first, last)) if extend => Some((first.min(i), last.max(i))),
+                Some((first, last)) if first == i && last == i => None,
+                _ => Some((i, i)),
+            };
+        }
+        if let Some((first, last)) = self.selection {
+            ui.separator();
+            if self.correction.ui(ui, core, first..=last) {
+                self.selection = None;
+            }
+        }
+        self.correction.notice_ui(ui);
+    }
+
+    /// テキスト全体を 1 つのレイアウトにまとめる
+    fn build(&mut self, ui: &egui::Ui, core: &TokenizerCore, dark_mode: bool) {
+        self.sections.clear();
+        if !tokens_match_text(&core.tokens, &core.input_text) {
+            self.job = None;
+            return;
+        }
+
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        let plain = TextFormat {
+            font_id: font_id.clone(),
+            color: ui.visuals().text_color(),
+            ..Default::default()
+        };
+        let boundary = TextFormat {
+            font_id,
+            color: ui.visuals().weak_text_color(),
+            ..Default::default()
+        };
+        let mut job = LayoutJob::default();
+        let mut sections = Vec::new();
+        let mut chars = 0;
+        let mut push = |job: &mut LayoutJob, text: &str, format: TextFormat, token| {
+            sections.push(Section {
+                char_start: chars,
+                token,
+            });
+            chars += text.chars().count();
+            job.append(text, 0.0, format);
+        };
+
+        let text = &core.input_text;
+        let mut pos = 0;
+        let mut previous_end = None;
+        for (i, token) in core.tokens.iter().enumerate() {
+            if token.start > pos {
+                push(&mut job, &text[pos..token.start], plain.clone(), None);
+            } else if self.show_boundaries && previous_end == Some(token.start) {
+                push(&mut job, BOUNDARY_MARK, boundary.clone(), None);
+            }
+            let mut format = plain.clone();
+            if self.highlighted.contains(&token.pos) {
+                format.background = pos_color(&token.pos, dark_mode);
+            }
+            let selected = self
+                .selection
+                .map(|(first, last)| (first..=last).contains(&i));
+            if selected.unwrap_or(false) {
+                format.underline = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+            }
+            push(&mut job, &token.text, format, Some(i));
+            pos = token.end;
+            previous_end = Some(token.end);
+        }
+        if pos < text.len() {
+            push(&mut job, &text[pos..], plain, None);
+        }
+        self.sections = sections;
+        self.job = Some(job);
+    }
+}
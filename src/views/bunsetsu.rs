@@ -0,0 +1,79 @@
+use super::table::{virtual_table, TableColumn};
+use eframe::egui;
+use jp_word_segment::{save_bunsetsu_to_csv, Bunsetsu, TokenizerCore};
+use rfd::FileDialog;
+
+/// 文節の一覧のビュー
+#[derive(Default)]
+pub struct BunsetsuView {
+    bunsetsu: Vec<Bunsetsu>,
+    pattern: String,         // 文節に含まれる文字列 (空ならすべて)
+    rows: Vec<usize>,        // 表示する `bunsetsu` の添字
+    revision: Option<u64>,   // `bunsetsu` を作った TokenizerCore の revision
+    applied: Option<String>, // `rows` を作った絞り込み条件
+}
+
+impl BunsetsuView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
+        if core.tokens.is_empty() {
+            ui.label("「解析」ボタンを押すと文節の一覧が表示されます。");
+            return;
+        }
+        if self.revision != Some(core.revision) {
+            self.bunsetsu = core.bunsetsu();
+            self.revision = Some(core.revision);
+            self.applied = None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("文節の絞り込み:");
+            ui.text_edit_singleline(&mut self.pattern);
+            if ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("bunsetsu.csv")
+                    .save_file()
+                {
+                    if let Err(e) = save_bunsetsu_to_csv(&self.bunsetsu, &core.tokens, path) {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+        if self.applied.as_ref() != Some(&self.pattern) {
+            self.rows = (0..self.bunsetsu.len())
+                .filter(|&i| self.bunsetsu[i].text.contains(self.pattern.as_str()))
+                .collect();
+            self.applied = Some(self.pattern.clone());
+        }
+        ui.label(format!(
+            "文節数: {} / 表示: {}",
+            self.bunsetsu.len(),
+            self.rows.len()
+        ));
+
+        let columns = [
+            TableColumn::new("番号", 60.0),
+            TableColumn::new("文節", 200.0),
+            TableColumn::new("主辞", 120.0),
+            TableColumn::new("主辞の品詞", 180.0),
+            TableColumn::new("構成語", 0.0),
+        ];
+        virtual_table(
+            ui,
+            "bunsetsu_table",
+            &columns,
+            self.rows.len(),
+            300.0,
+            |row, index| {
+                let bunsetsu = &self.bunsetsu[self.rows[index]];
+                let head = bunsetsu.head.map(|i| &core.tokens[i]);
+                row.cell((bunsetsu.id + 1).to_string());
+                row.cell(bunsetsu.text.as_str());
+                row.cell(head.map(|t| t.text.as_str()).unwrap_or(""));
+                row.cell(head.map(|t| t.pos_path()).unwrap_or_default());
+                row.cell(bunsetsu.parts_text(&core.tokens));
+            },
+        );
+    }
+}
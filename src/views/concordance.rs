@@ -0,0 +1,197 @@
+use super::table::{virtual_table, TableColumn};
+use eframe::egui;
+use jp_word_segment::{
+    find_unit, ConcordanceQuery, ConcordanceResult, StructureUnit, TextUnit, TokenizerCore,
+};
+
+/// コンコーダンス検索 (KWIC) のビュー
+pub struct ConcordanceView {
+    keyword: String,
+    context_size: usize,
+    results: Vec<ConcordanceResult>,
+    searched: bool,
+    unit: Option<StructureUnit>, // 結果を分類する構造の単位
+    unit_filter: Option<usize>,  // 表示する単位 (`units` の添字、None ですべて)
+    units: Vec<TextUnit>,
+    result_units: Vec<Option<usize>>, // 各結果が属する単位
+    unit_counts: Vec<usize>,          // 単位ごとの件数
+    rows: Vec<usize>,                 // 表示する結果の添字
+    revision: Option<u64>,            // 分類に使った TokenizerCore の revision
+}
+
+impl Default for ConcordanceView {
+    fn default() -> Self {
+        let query = ConcordanceQuery::default();
+        Self {
+            keyword: query.keyword,
+            context_size: query.context_size,
+            results: Vec::new(),
+            searched: false,
+            unit: query.unit,
+            unit_filter: None,
+            units: Vec::new(),
+            result_units: Vec::new(),
+            unit_counts: Vec::new(),
+            rows: Vec::new(),
+            revision: None,
+        }
+    }
+}
+
+impl ConcordanceView {
+    /// プロジェクトに保存する検索条件
+    pub fn query(&self) -> ConcordanceQuery {
+        ConcordanceQuery {
+            keyword: self.keyword.clone(),
+            context_size: self.context_size,
+            unit: self.unit,
+        }
+    }
+
+    /// 検索条件を復元し、解析済みなら検索し直す
+    pub fn restore(&mut self, query: ConcordanceQuery, core: &TokenizerCore) {
+        self.keyword = query.keyword;
+        self.context_size = query.context_size;
+        self.unit = query.unit;
+        if !core.tokens.is_empty() && !self.keyword.is_empty() {
+            self.search(core);
+        } else {
+            self.results.clear();
+            self.searched = false;
+            self.revision = None;
+        }
+    }
+
+    /// 単語を検索する
+    pub fn search_word(&mut self, word: &str, core: &TokenizerCore) {
+        self.keyword = word.to_string();
+        self.search(core);
+    }
+
+    fn search(&mut self, core: &TokenizerCore) {
+        self.results = core.search_concordance(&self.keyword, self.context_size);
+        self.searched = true;
+        self.unit_filter = None;
+        self.revision = None;
+    }
+
+    /// 結果を構造の単位で分類し、表示する行を決め直す
+    fn refresh_rows(&mut self, core: &TokenizerCore) {
+        self.units = self
+            .unit
+            .map(|unit| core.structure.units(unit))
+            .unwrap_or_default();
+        self.result_units = self
+            .results
+            .iter()
+            .map(|r| find_unit(&self.units, r.line_number))
+            .collect();
+        self.unit_counts = vec![0; self.units.len()];
+        for unit in self.result_units.iter().flatten() {
+            self.unit_counts[*unit] += 1;
+        }
+        self.rows = (0..self.results.len())
+            .filter(|&i| {
+                self.unit_filter
+                    .map(|filter| self.result_units[i] == Some(filter))
+                    .unwrap_or(true)
+            })
+            .collect();
+        self.revision = Some(core.revision);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
+        ui.horizontal(|ui| {
+            ui.label("検索キーワード:");
+            ui.text_edit_singleline(&mut self.keyword);
+            ui.label("文脈サイズ:");
+            ui.add(
+                egui::DragValue::new(&mut self.context_size)
+                    .speed(1.0)
+                    .clamp_range(1..=20),
+            );
+        });
+
+        if ui.button("検索").clicked() && !self.keyword.is_empty() {
+            self.search(core);
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("構造の単位で分類:");
+            let previous = self.unit;
+            egui::ComboBox::from_id_source("concordance_unit")
+                .selected_text(self.unit.map(|u| u.label()).unwrap_or("なし"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.unit, None, "なし");
+                    for unit in StructureUnit::ALL {
+                        ui.selectable_value(&mut self.unit, Some(unit), unit.label());
+                    }
+                });
+            if self.unit != previous {
+                self.unit_filter = None;
+                self.revision = None;
+            }
+        });
+
+        if !self.searched {
+            return;
+        }
+        if self.revision != Some(core.revision) {
+            self.refresh_rows(core);
+        }
+
+        ui.label(format!("検索結果: {}件", self.results.len()));
+        if !self.units.is_empty() {
+            let previous = self.unit_filter;
+            ui.horizontal_wrapped(|ui| {
+                ui.label("単位ごとの件数:");
+                ui.selectable_value(
+                    &mut self.unit_filter,
+                    None,
+                    format!("すべて ({})", self.results.len()),
+                );
+                for (i, unit) in self.units.iter().enumerate() {
+                    if self.unit_counts[i] > 0 {
+                        ui.selectable_value(
+                            &mut self.unit_filter,
+                            Some(i),
+                            format!("{} ({})", unit.label, self.unit_counts[i]),
+                        );
+                    }
+                }
+            });
+            if self.unit_filter != previous {
+                self.refresh_rows(core);
+            }
+        }
+
+        let mut columns = vec![TableColumn::new("行番号", 60.0)];
+        if !self.units.is_empty() {
+            columns.push(TableColumn::new("単位", 140.0));
+        }
+        columns.extend([
+            TableColumn::new("左文脈", 240.0),
+            TableColumn::new("キーワード", 100.0),
+            TableColumn::new("右文脈", 0.0),
+        ]);
+        virtual_table(
+            ui,
+            "concordance_table",
+            &columns,
+            self.rows.len(),
+            300.0,
+            |row, index| {
+                let i = self.rows[index];
+                let result = &self.results[i];
+                row.cell(result.line_number.to_string());
+                if !self.units.is_empty() {
+                    let unit = self.result_units[i].map(|u| self.units[u].label.as_str());
+                    row.cell(unit.unwrap_or("-"));
+                }
+                row.cell(result.left_context.as_str());
+                row.cell(result.keyword.as_str());
+                row.cell(result.right_context.as_str());
+            },
+        );
+    }
+}
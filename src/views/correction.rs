@@ -0,0 +1,287 @@
+use eframe::egui;
+use jp_word_segment::{classify_correction, load_user_words, TokenizerCore, UserWord, IPADIC_POS};
+use rfd::FileDialog;
+use std::ops::RangeInclusive;
+
+/// 分割の区切りとして入力する文字
+const SPLIT_MARK: char = '|';
+
+/// 選択したトークンの分割を修正し、ユーザー辞書に登録するビュー
+#[derive(Default)]
+pub struct CorrectionView {
+    shown: Option<(u64, usize, usize)>, // 編集欄に反映済みの選択 (revision, 先頭, 末尾)
+    original: String,                   // 選択範囲のテキスト
+    text: String,                       // 区切りを入れた修正後のテキスト
+    words: Vec<UserWord>,               // 区切った語ごとの品詞・読み
+    allow_single: bool,                 // 1 文字の語の登録を確認済み
+    notice: Option<String>,             // 直前の登録で指定した分割にならなかった時の説明
+    error: Option<String>,
+    registered: Vec<UserWord>,                       // 登録済みの語
+    registered_shown: Option<(Option<String>, u64)>, // `registered` を読んだ辞書と revision
+}
+
+/// 選択範囲のトークンから語の品詞・読みの初期値を決める
+fn default_word(surface: &str, core: &TokenizerCore, range: &RangeInclusive<usize>) -> UserWord {
+    let tokens = &core.tokens[range.clone()];
+    if let Some(token) = tokens.iter().find(|t| t.text == surface) {
+        return UserWord {
+            surface: surface.to_string(),
+            pos: token.pos_path(),
+            reading: token.reading.clone(),
+            cost: None,
+        };
+    }
+    let whole: String = tokens.iter().map(|t| t.text.as_str()).collect();
+    if surface == whole {
+        return UserWord {
+            surface: surface.to_string(),
+            pos: tokens[0].pos_path(),
+            reading: tokens.iter().map(|t| t.reading.as_str()).collect(),
+            cost: None,
+        };
+    }
+    UserWord {
+        surface: surface.to_string(),
+        pos: IPADIC_POS[0].to_string(),
+        reading: String::new(),
+        cost: None,
+    }
+}
+
+/// 解析し直した結果が指定した分割と異なれば、その説明
+fn split_notice(
+    core: &TokenizerCore,
+    span: std::ops::Range<usize>,
+    surfaces: &[&str],
+) -> Option<String> {
+    let result: Vec<&str> = core
+        .tokens
+        .iter()
+        .filter(|t| t.start >= span.start && t.end <= span.end)
+        .map(|t| t.text.as_str())
+        .collect();
+    (result != surfaces).then(|| {
+        format!(
+            "解析し直した結果は「{}」で、指定した分割「{}」になりませんでした。\
+             前後の語とのつながりによっては、分割した語を登録しても反映されないことがあります。",
+            result.join(&SPLIT_MARK.to_string()),
+            surfaces.join(&SPLIT_MARK.to_string())
+        )
+    })
+}
+
+impl CorrectionView {
+    /// 選択範囲の修正欄を表示する (登録して解析し直したら true)
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        core: &mut TokenizerCore,
+        range: RangeInclusive<usize>,
+    ) -> bool {
+        // 解析後にテキストが編集されていれば選択したトークンと位置が合わない
+        let Some(tokens) = core.tokens.get(range.clone()) else {
+            return false;
+        };
+        let matches_text = tokens
+            .iter()
+            .all(|t| core.input_text.get(t.start..t.end) == Some(t.text.as_str()));
+        if !matches_text {
+            ui.colored_label(
+                egui::Color32::RED,
+                "解析後にテキストが変更されています。「解析」ボタンで解析し直してください。",
+            );
+            return false;
+        }
+        let span = tokens[0].start..tokens[tokens.len() - 1].end;
+
+        let selection = (core.revision, *range.start(), *range.end());
+        if self.shown != Some(selection) {
+            self.original = core.input_text[span.clone()].to_string();
+            self.text = self.original.clone();
+            self.words.clear();
+            self.allow_single = false;
+            self.error = None;
+            self.notice = None;
+            self.shown = Some(selection);
+        }
+
+        ui.label(format!("選択: 「{}」", self.original));
+        let contiguous = tokens.windows(2).all(|pair| pair[0].end == pair[1].start);
+        if !contiguous || self.original.chars().any(char::is_whitespace) {
+            ui.colored_label(
+                egui::Color32::RED,
+                "空白をまたぐ範囲は登録できません。選択し直してください。",
+            );
+            return false;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("分割 (「{}」で区切る):", SPLIT_MARK));
+            ui.text_edit_singleline(&mut self.text);
+            if ui.button("元に戻す").clicked() {
+                self.text = self.original.clone();
+            }
+        });
+
+        // 区切りが変わった語だけ初期値を入れ直す
+        let surfaces: Vec<&str> = self.text.split(SPLIT_MARK).collect();
+        if surfaces.concat() != self.original || surfaces.iter().any(|s| s.is_empty()) {
+            ui.colored_label(
+                egui::Color32::RED,
+                "区切りを除いた文字列が選択範囲と一致しません。",
+            );
+            return false;
+        }
+        let words: Vec<UserWord> = surfaces
+            .iter()
+            .map(|&surface| {
+                self.words
+                    .iter()
+                    .find(|w| w.surface == surface)
+                    .cloned()
+                    .unwrap_or_else(|| default_word(surface, core, &range))
+            })
+            .collect();
+        self.words = words;
+        let kinds = classify_correction(&core.tokens[range.clone()], &self.words);
+
+        egui::Grid::new("correction_words")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("表層形");
+                ui.strong("品詞");
+                ui.strong("読み");
+                ui.strong("登録");
+                ui.end_row();
+                for (i, (word, kind)) in self.words.iter_mut().zip(&kinds).enumerate() {
+                    ui.label(&word.surface);
+                    egui::ComboBox::from_id_source(("correction_pos", i))
+                        .selected_text(word.pos.as_str())
+                        .width(220.0)
+                        .show_ui(ui, |ui| {
+                            for pos in IPADIC_POS {
+                                ui.selectable_value(&mut word.pos, pos.to_string(), pos);
+                            }
+                        });
+                    ui.text_edit_singleline(&mut word.reading);
+                    ui.label(kind.label());
+                    ui.end_row();
+                }
+            });
+
+        let mut registered = false;
+        ui.horizontal(|ui| {
+            ui.label("ユーザー辞書:");
+            ui.label(
+                core.settings
+                    .user_dictionary
+                    .as_deref()
+                    .unwrap_or("未設定 (登録時に保存先を選択)"),
+            );
+        });
+        let add: Vec<UserWord> = self
+            .words
+            .iter()
+            .zip(&kinds)
+            .filter_map(|(word, kind)| kind.user_word(word))
+            .collect();
+        if add.is_empty() {
+            ui.label("登録する語がありません (元の語から変わっていません)。");
+        }
+        // 1 文字の語は他の箇所の分割も変えやすいので確認してから登録する
+        let single: Vec<&str> = add
+            .iter()
+            .filter(|w| w.surface.chars().count() == 1)
+            .map(|w| w.surface.as_str())
+            .collect();
+        if !single.is_empty() {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!(
+                    "1 文字の語 ({}) を登録すると、他の箇所の分割も変わることがあります。",
+                    single.join("、")
+                ),
+            );
+            ui.checkbox(&mut self.allow_single, "1 文字の語も登録する");
+        }
+        let enabled = !add.is_empty() && (single.is_empty() || self.allow_single);
+        if ui
+            .add_enabled(enabled, egui::Button::new("ユーザー辞書に登録して再解析"))
+            .clicked()
+        {
+            let path = core
+                .settings
+                .user_dictionary
+                .clone()
+                .map(std::path::PathBuf::from)
+                .or_else(|| {
+                    FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("user_dictionary.csv")
+                        .save_file()
+                });
+            if let Some(path) = path {
+                match core.register_user_words(path, &add) {
+                    Ok(()) => {
+                        self.error = None;
+                        self.notice = split_notice(core, span, &surfaces);
+                        registered = true;
+                    }
+                    Err(e) => self.error = Some(format!("登録できませんでした: {}", e)),
+                }
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        self.registered_ui(ui, core);
+        registered
+    }
+
+    /// 直前の登録の結果 (指定した分割にならなかった場合の説明)
+    pub fn notice_ui(&self, ui: &mut egui::Ui) {
+        if let Some(notice) = &self.notice {
+            ui.colored_label(egui::Color32::YELLOW, notice);
+        }
+    }
+
+    /// 登録済みの語の一覧 (削除すると解析し直す)
+    fn registered_ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        let Some(path) = core.settings.user_dictionary.clone() else {
+            return;
+        };
+        let shown = (Some(path.clone()), core.revision);
+        if self.registered_shown.as_ref() != Some(&shown) {
+            self.registered = load_user_words(path.clone().into()).unwrap_or_default();
+            self.registered_shown = Some(shown);
+        }
+
+        let mut removed = None;
+        egui::CollapsingHeader::new(format!("登録済みの語 ({})", self.registered.len()))
+            .id_source("correction_registered")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("correction_registered_scroll")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for word in &self.registered {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("削除").clicked() {
+                                    removed = Some(word.surface.clone());
+                                }
+                                ui.label(format!(
+                                    "{}  {}  {}",
+                                    word.surface, word.pos, word.reading
+                                ));
+                            });
+                        }
+                    });
+            });
+        if let Some(surface) = removed {
+            if let Err(e) = core.remove_user_words(path.into(), &[surface]) {
+                self.error = Some(format!("削除できませんでした: {}", e));
+            }
+            self.registered_shown = None;
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::pos_tree::pos_filter_ui;
+use super::table::{virtual_table, TableColumn};
+use eframe::egui;
+use jp_word_segment::{
+    ipadic_pos_children, FrequencyEntry, FrequencyFilter, FrequencySortKey, PosFilter,
+    TokenizerCore,
+};
+
+/// 異なり語ごとの頻度表のビュー (見出しクリックで並べ替え、行クリックでコンコーダンス検索)
+pub struct FrequencyView {
+    filter: FrequencyFilter,
+    filter_pos: bool,      // 品詞で絞り込む
+    pos_filter: PosFilter, // 品詞で絞り込む場合の条件
+    sort_key: FrequencySortKey,
+    sort_descending: bool,
+    entries: Vec<FrequencyEntry>,
+    rows: Vec<usize>, // 絞り込み・並べ替え後の `entries` の添字
+    error: Option<String>,
+    revision: Option<u64>, // `entries` を作った TokenizerCore の revision
+    applied: Option<(FrequencyFilter, FrequencySortKey, bool)>, // `rows` を作った条件
+}
+
+impl Default for FrequencyView {
+    fn default() -> Self {
+        Self {
+            filter: FrequencyFilter::default(),
+            filter_pos: false,
+            pos_filter: PosFilter::including(&ipadic_pos_children(None)),
+            sort_key: FrequencySortKey::Frequency,
+            sort_descending: true,
+            entries: Vec::new(),
+            rows: Vec::new(),
+            error: None,
+            revision: None,
+            applied: None,
+        }
+    }
+}
+
+impl FrequencyView {
+    /// 頻度表を表示する (クリックされた行の単語を返す)
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) -> Option<String> {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("単語の絞り込み:");
+            ui.text_edit_singleline(&mut self.filter.pattern);
+            ui.checkbox(&mut self.filter.use_regex, "正規表現");
+            ui.label("最小頻度:");
+            ui.add(
+                egui::DragValue::new(&mut self.filter.min_frequency)
+                    .speed(1.0)
+                    .clamp_range(1..=10000),
+            );
+            ui.checkbox(&mut self.filter_pos, "品詞で絞り込む");
+        });
+        if self.filter_pos {
+            egui::CollapsingHeader::new("対象品詞")
+                .id_source("frequency_pos_filter")
+                .show(ui, |ui| {
+                    pos_filter_ui(ui, "frequency_pos_tree", &mut self.pos_filter);
+                });
+        }
+        self.filter.pos_filter = self.filter_pos.then(|| self.pos_filter.clone());
+
+        // 解析結果・条件が変わった時だけ作り直す
+        if self.revision != Some(core.revision) {
+            self.entries = core.frequency_entries();
+            self.revision = Some(core.revision);
+            self.applied = None;
+        }
+        let conditions = (self.filter.clone(), self.sort_key, self.sort_descending);
+        if self.applied.as_ref() != Some(&conditions) {
+            match self.filter.apply(&self.entries) {
+                Ok(mut rows) => {
+                    self.sort_key
+                        .sort(&self.entries, &mut rows, self.sort_descending);
+                    self.rows = rows;
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+            self.applied = Some(conditions);
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("正規表現のエラー: {}", error));
+        }
+        ui.label(format!(
+            "異なり語数: {} / 表示: {} (行をクリックするとコンコーダンス検索)",
+            self.entries.len(),
+            self.rows.len()
+        ));
+
+        let keys = [
+            (FrequencySortKey::Word, 160.0),
+            (FrequencySortKey::Reading, 140.0),
+            (FrequencySortKey::Pos, 220.0),
+            (FrequencySortKey::Frequency, 0.0),
+        ];
+        let columns: Vec<TableColumn> = keys
+            .iter()
+            .map(|&(key, width)| {
+                let title = if self.sort_key == key {
+                    let arrow = if self.sort_descending { "▼" } else { "▲" };
+                    format!("{} {}", key.label(), arrow)
+                } else {
+                    key.label().to_string()
+                };
+                TableColumn::new(title, width)
+            })
+            .collect();
+
+        let mut clicked_row = None;
+        let clicked_column = virtual_table(
+            ui,
+            "frequency_table",
+            &columns,
+            self.rows.len(),
+            300.0,
+            |row, index| {
+                let entry = &self.entries[self.rows[index]];
+                if row.clickable_cell(entry.word.as_str()).clicked() {
+                    clicked_row = Some(index);
+                }
+                row.cell(entry.reading.as_str());
+                row.cell(entry.pos.as_str());
+                row.cell(entry.frequency.to_string());
+            },
+        );
+
+        if let Some(column) = clicked_column {
+            let key = keys[column].0;
+            if self.sort_key == key {
+                self.sort_descending = !self.sort_descending;
+            } else {
+                self.sort_key = key;
+                self.sort_descending = key == FrequencySortKey::Frequency;
+            }
+        }
+        clicked_row.map(|index| self.entries[self.rows[index]].word.clone())
+    }
+}
@@ -0,0 +1,182 @@
+use eframe::egui;
+use jp_word_segment::{
+    load_frequency_list, save_keyness_to_csv, KeynessConfig, KeynessEntry, KeynessMeasure,
+    KeynessResult, TokenizerCore,
+};
+use rfd::FileDialog;
+use std::collections::HashMap;
+
+/// 参照コーパスとの比較による特徴語 (キーネス) 分析のビュー
+#[derive(Default)]
+pub struct KeynessView {
+    config: KeynessConfig,
+    reference: Option<HashMap<String, usize>>,
+    reference_name: String,
+    result: Option<KeynessResult>,
+}
+
+impl KeynessView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
+        // 参照コーパスの読み込み
+        ui.horizontal_wrapped(|ui| {
+            if ui.button("参照テキストを開く").clicked() {
+                if let Some(path) = FileDialog::new().pick_file() {
+                    match std::fs::read_to_string(&path) {
+                        Ok(text) => {
+                            self.reference = Some(core.count_frequencies(&text));
+                            self.reference_name = path.to_string_lossy().into_owned();
+                            self.result = None;
+                        }
+                        Err(e) => eprintln!("ファイル読み込みエラー: {}", e),
+                    }
+                }
+            }
+            if ui.button("参照頻度リストを開く").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV / TSV", &["csv", "tsv", "txt"])
+                    .pick_file()
+                {
+                    match load_frequency_list(path.clone()) {
+                        Ok(list) => {
+                            self.reference = Some(list);
+                            self.reference_name = path.to_string_lossy().into_owned();
+                            self.result = None;
+                        }
+                        Err(e) => eprintln!("頻度リスト読み込みエラー: {}", e),
+                    }
+                }
+            }
+            if !core.word_frequencies.is_empty() && ui.button("現在の頻度リストを保存").clicked()
+            {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("frequency_list.csv")
+                    .save_file()
+                {
+                    if let Err(e) = core.save_frequency_list(path) {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+
+        match &self.reference {
+            Some(reference) => {
+                ui.label(format!(
+                    "参照コーパス: {} ({} 語 / 異なり {} 語)",
+                    self.reference_name,
+                    reference.values().sum::<usize>(),
+                    reference.len()
+                ));
+            }
+            None => {
+                ui.label("参照テキストまたは参照頻度リストを読み込んでください。");
+            }
+        }
+
+        // 設定 UI
+        ui.horizontal_wrapped(|ui| {
+            ui.label("並べ替え:");
+            egui::ComboBox::from_id_source("keyness_measure")
+                .selected_text(self.config.measure.label())
+                .show_ui(ui, |ui| {
+                    for measure in KeynessMeasure::ALL {
+                        ui.selectable_value(&mut self.config.measure, measure, measure.label());
+                    }
+                });
+            ui.label("最小頻度:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_freq)
+                    .speed(1.0)
+                    .clamp_range(1..=1000),
+            );
+            ui.label("LL 閾値:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_log_likelihood)
+                    .speed(0.1)
+                    .clamp_range(0.0..=100.0),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            let ready = self.reference.is_some() && !core.word_frequencies.is_empty();
+            if ui
+                .add_enabled(ready, egui::Button::new("特徴語を計算"))
+                .clicked()
+            {
+                if let Some(reference) = &self.reference {
+                    self.result = Some(core.compute_keyness(reference, &self.config));
+                }
+            }
+            if let Some(result) = &self.result {
+                if ui.button("CSVファイルに保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("keyness.csv")
+                        .save_file()
+                    {
+                        if let Err(e) = save_keyness_to_csv(result, path) {
+                            eprintln!("CSV保存エラー: {}", e);
+                        }
+                    }
+                }
+            }
+        });
+        if core.word_frequencies.is_empty() {
+            ui.label("先に「解析」ボタンで対象テキストを解析してください。");
+        }
+
+        let Some(result) = &self.result else {
+            return;
+        };
+        ui.label(format!(
+            "対象 {} 語 / 参照 {} 語",
+            result.target_total, result.reference_total
+        ));
+        ui.columns(2, |columns| {
+            keyness_table(
+                &mut columns[0],
+                "keyness_positive",
+                "正の特徴語 (対象で多い)",
+                &result.positive,
+            );
+            keyness_table(
+                &mut columns[1],
+                "keyness_negative",
+                "負の特徴語 (対象で少ない)",
+                &result.negative,
+            );
+        });
+    }
+}
+
+/// 特徴語の一覧表を描画する
+fn keyness_table(ui: &mut egui::Ui, id: &str, title: &str, entries: &[KeynessEntry]) {
+    ui.label(format!("{}: {}件", title, entries.len()));
+    egui::ScrollArea::vertical()
+        .id_source(id)
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new(id).striped(true).show(ui, |ui| {
+                ui.strong("単語");
+                ui.strong("対象");
+                ui.strong("参照");
+                ui.strong("LL");
+                ui.strong("χ²");
+                ui.strong("%DIFF");
+                ui.strong("Log Ratio");
+                ui.end_row();
+
+                for entry in entries {
+                    ui.label(&entry.word);
+                    ui.label(entry.target_freq.to_string());
+                    ui.label(entry.reference_freq.to_string());
+                    ui.label(format!("{:.2}", entry.log_likelihood));
+                    ui.label(format!("{:.2}", entry.chi_square));
+                    ui.label(format!("{:.1}", entry.percent_diff));
+                    ui.label(format!("{:.2}", entry.log_ratio));
+                    ui.end_row();
+                }
+            });
+        });
+}
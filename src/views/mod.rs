@@ -0,0 +1,30 @@
+//! 解析結果ごとの GUI ビュー
+
+pub mod annotated_text;
+pub mod bunsetsu;
+pub mod concordance;
+pub mod correction;
+pub mod frequency;
+pub mod keyness;
+pub mod ngram;
+pub mod pos_tree;
+pub mod sentences;
+pub mod stopwords;
+pub mod structure;
+pub mod table;
+pub mod tokenizer_settings;
+pub mod unknown_words;
+
+pub use annotated_text::AnnotatedTextView;
+pub use bunsetsu::BunsetsuView;
+pub use concordance::ConcordanceView;
+pub use frequency::FrequencyView;
+pub use keyness::KeynessView;
+pub use ngram::NgramView;
+pub use pos_tree::pos_filter_ui;
+pub use sentences::SentenceView;
+pub use stopwords::StopwordView;
+pub use structure::StructureView;
+pub use table::{virtual_table, TableColumn};
+pub use tokenizer_settings::TokenizerSettingsView;
+pub use unknown_words::UnknownWordView;
@@ -0,0 +1,104 @@
+use eframe::egui;
+use jp_word_segment::{save_ngrams_to_csv, DocumentSplit, NgramConfig, NgramEntry, TokenizerCore};
+use rfd::FileDialog;
+
+/// n-gram (語連鎖) 抽出の設定と結果表示を行うビュー
+#[derive(Default)]
+pub struct NgramView {
+    config: NgramConfig,
+    pos_pattern_input: String,
+    results: Vec<NgramEntry>,
+    computed: bool,
+}
+
+impl NgramView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
+        // 設定 UI
+        ui.horizontal_wrapped(|ui| {
+            ui.label("語数:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_n)
+                    .speed(1.0)
+                    .clamp_range(NgramConfig::MIN_N..=NgramConfig::MAX_N),
+            );
+            ui.label("〜");
+            ui.add(
+                egui::DragValue::new(&mut self.config.max_n)
+                    .speed(1.0)
+                    .clamp_range(NgramConfig::MIN_N..=NgramConfig::MAX_N),
+            );
+            ui.label("最小頻度:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_freq)
+                    .speed(1.0)
+                    .clamp_range(1..=1000),
+            );
+            ui.label("最小レンジ:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_range)
+                    .speed(1.0)
+                    .clamp_range(1..=1000),
+            );
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("文書の区切り:");
+            egui::ComboBox::from_id_source("ngram_document_split")
+                .selected_text(self.config.document_split.label())
+                .show_ui(ui, |ui| {
+                    for split in DocumentSplit::ALL {
+                        ui.selectable_value(&mut self.config.document_split, split, split.label());
+                    }
+                });
+            ui.checkbox(&mut self.config.within_sentence, "文境界をまたがない");
+        });
+        ui.horizontal(|ui| {
+            ui.label("品詞パターン (例: 名詞+の+名詞, 空欄で制限なし):");
+            ui.text_edit_singleline(&mut self.pos_pattern_input);
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("n-gram を抽出").clicked() {
+                self.config.set_pos_pattern(&self.pos_pattern_input);
+                self.results = core.compute_ngrams(&self.config);
+                self.computed = true;
+            }
+            if !self.results.is_empty() && ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("ngrams.csv")
+                    .save_file()
+                {
+                    if let Err(e) = save_ngrams_to_csv(&self.results, path) {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+
+        if !self.computed {
+            return;
+        }
+
+        ui.label(format!("抽出結果: {}件", self.results.len()));
+        egui::ScrollArea::vertical()
+            .id_source("ngram_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("ngram_grid").striped(true).show(ui, |ui| {
+                    ui.heading("n-gram");
+                    ui.heading("品詞列");
+                    ui.heading("頻度");
+                    ui.heading("レンジ");
+                    ui.end_row();
+
+                    for entry in &self.results {
+                        ui.label(entry.text());
+                        ui.label(entry.pos_text());
+                        ui.label(entry.frequency.to_string());
+                        ui.label(entry.range.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+}
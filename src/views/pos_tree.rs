@@ -0,0 +1,70 @@
+use eframe::egui;
+use jp_word_segment::{ipadic_pos_children, PosFilter};
+
+/// IPADIC の品詞階層をチェックボックスの木で表示し、絞り込み条件を編集する
+///
+/// 変更があれば true を返す。
+pub fn pos_filter_ui(ui: &mut egui::Ui, id: &str, filter: &mut PosFilter) -> bool {
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        ui.label(format!("対象: {}", summary(&filter.include)));
+        ui.label(format!("除外: {}", summary(&filter.exclude)));
+    });
+    egui::ScrollArea::vertical()
+        .id_source(id)
+        .max_height(220.0)
+        .show(ui, |ui| {
+            changed |= pos_subtree_ui(ui, id, None, filter);
+        });
+    changed
+}
+
+fn pos_subtree_ui(
+    ui: &mut egui::Ui,
+    id: &str,
+    parent: Option<&str>,
+    filter: &mut PosFilter,
+) -> bool {
+    let mut changed = false;
+    for path in ipadic_pos_children(parent) {
+        let name = path.rsplit('-').next().unwrap_or(&path).to_string();
+        let has_children = !ipadic_pos_children(Some(&path)).is_empty();
+        let checkbox = |ui: &mut egui::Ui, filter: &mut PosFilter| {
+            let mut selected = filter.matches(&path);
+            let text = if filter.is_mixed(&path) {
+                format!("{} (一部)", name)
+            } else {
+                name.clone()
+            };
+            if ui.checkbox(&mut selected, text).changed() {
+                filter.set(&path, selected);
+                return true;
+            }
+            false
+        };
+        if has_children {
+            let state = egui::collapsing_header::CollapsingState::load_with_default_open(
+                ui.ctx(),
+                ui.make_persistent_id((id, &path)),
+                false,
+            );
+            let (_, header, body) = state
+                .show_header(ui, |ui| checkbox(ui, filter))
+                .body(|ui| pos_subtree_ui(ui, id, Some(&path), filter));
+            changed |= header.inner;
+            changed |= body.map(|b| b.inner).unwrap_or(false);
+        } else {
+            changed |= checkbox(ui, filter);
+        }
+    }
+    changed
+}
+
+/// 規則の一覧の表示用文字列
+fn summary(rules: &[String]) -> String {
+    if rules.is_empty() {
+        "なし".to_string()
+    } else {
+        rules.join(", ")
+    }
+}
@@ -0,0 +1,106 @@
+use eframe::egui;
+use jp_word_segment::{
+    save_sentences_to_csv, split_sentence_spans, SentenceSpan, SentenceSplitConfig, TokenizerCore,
+};
+use rfd::FileDialog;
+
+/// 文分割の設定と結果表示を行うビュー
+pub struct SentenceView {
+    config: SentenceSplitConfig,
+    terminators_input: String,
+    brackets_input: String,
+    results: Vec<SentenceSpan>,
+    computed: bool,
+}
+
+impl Default for SentenceView {
+    fn default() -> Self {
+        let config = SentenceSplitConfig::default();
+        Self {
+            terminators_input: config.terminators.iter().collect(),
+            brackets_input: config.brackets.iter().flat_map(|&(o, c)| [o, c]).collect(),
+            config,
+            results: Vec::new(),
+            computed: false,
+        }
+    }
+}
+
+impl SentenceView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &TokenizerCore) {
+        // 設定 UI
+        ui.horizontal_wrapped(|ui| {
+            ui.label("文末記号:");
+            ui.text_edit_singleline(&mut self.terminators_input);
+            ui.label("括弧 (開き・閉じの順に並べる):");
+            ui.text_edit_singleline(&mut self.brackets_input);
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.config.keep_terminators, "文末記号を文に含める");
+            ui.checkbox(
+                &mut self.config.ignore_line_wraps,
+                "改行で区切らない (空行でのみ区切る)",
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("文に分割").clicked() {
+                self.config.terminators = self
+                    .terminators_input
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                let brackets: Vec<char> = self
+                    .brackets_input
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                self.config.brackets = brackets
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .collect();
+                self.results = split_sentence_spans(&core.input_text, &self.config);
+                self.computed = true;
+            }
+            if !self.results.is_empty() && ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("sentences.csv")
+                    .save_file()
+                {
+                    if let Err(e) = save_sentences_to_csv(&self.results, path) {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+
+        if !self.computed {
+            return;
+        }
+
+        ui.label(format!("文の数: {}", self.results.len()));
+        egui::ScrollArea::vertical()
+            .id_source("sentence_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("sentence_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.heading("番号");
+                        ui.heading("行");
+                        ui.heading("位置");
+                        ui.heading("文");
+                        ui.end_row();
+
+                        for (i, span) in self.results.iter().enumerate() {
+                            ui.label((i + 1).to_string());
+                            ui.label(span.line.to_string());
+                            ui.label(format!("{}–{}", span.char_start, span.char_end));
+                            ui.label(&span.text);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
@@ -0,0 +1,92 @@
+use eframe::egui;
+use jp_word_segment::{
+    load_stopwords, parse_stopwords, save_stopwords, StopwordPreset, TokenizerCore,
+};
+use rfd::FileDialog;
+use std::collections::HashSet;
+
+/// ストップワードリストの編集ビュー
+#[derive(Default)]
+pub struct StopwordView {
+    preset: StopwordPreset,
+    text: String,           // 編集中のリスト (1 行に 1 語)
+    shown: HashSet<String>, // `text` に反映済みのリスト
+}
+
+impl StopwordView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        // 他の操作 (プロジェクトの読み込みなど) で変わったリストを編集欄に反映する
+        if self.shown != core.stopwords {
+            self.text = list_text(&core.stopwords);
+            self.shown = core.stopwords.clone();
+        }
+
+        ui.label(format!(
+            "登録数: {} 語 (頻度表・CSV・n-gram・共起ネットワーク・特徴語分析から除外)",
+            core.stopwords.len()
+        ));
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_source("stopword_preset")
+                .selected_text(self.preset.label())
+                .show_ui(ui, |ui| {
+                    for preset in StopwordPreset::ALL {
+                        ui.selectable_value(&mut self.preset, preset, preset.label());
+                    }
+                });
+            if ui.button("同梱リストを追加").clicked() {
+                let mut words = core.stopwords.clone();
+                words.extend(self.preset.words());
+                core.set_stopwords(words);
+            }
+            if ui.button("ファイルから追加").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("テキスト", &["txt"])
+                    .pick_file()
+                {
+                    match load_stopwords(path) {
+                        Ok(loaded) => {
+                            let mut words = core.stopwords.clone();
+                            words.extend(loaded);
+                            core.set_stopwords(words);
+                        }
+                        Err(e) => eprintln!("ファイル読み込みエラー: {}", e),
+                    }
+                }
+            }
+            if !core.stopwords.is_empty() {
+                if ui.button("ファイルに保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("テキスト", &["txt"])
+                        .set_file_name("stopwords.txt")
+                        .save_file()
+                    {
+                        if let Err(e) = save_stopwords(&core.stopwords, path) {
+                            eprintln!("ストップワード保存エラー: {}", e);
+                        }
+                    }
+                }
+                if ui.button("すべて削除").clicked() {
+                    core.set_stopwords(HashSet::new());
+                }
+            }
+        });
+
+        ui.label("編集 (1 行に 1 語、# 以降はコメント):");
+        egui::ScrollArea::vertical()
+            .id_source("stopword_text")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                ui.add(egui::TextEdit::multiline(&mut self.text).desired_rows(6));
+            });
+        if ui.button("編集内容を適用").clicked() {
+            core.set_stopwords(parse_stopwords(&self.text));
+        }
+    }
+}
+
+/// 編集欄に表示する文字列 (1 行に 1 語)
+fn list_text(words: &HashSet<String>) -> String {
+    let mut sorted: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+    sorted.sort();
+    sorted.join("\n")
+}
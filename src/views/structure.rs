@@ -0,0 +1,199 @@
+use super::table::{virtual_table, TableColumn};
+use eframe::egui;
+use jp_word_segment::{
+    save_unit_frequencies_to_csv, StructureConfig, StructureUnit, TextUnit, TokenizerCore,
+};
+use rfd::FileDialog;
+use std::collections::HashMap;
+
+/// 章・節・段落の検出と単位ごとの頻度比較を行うビュー
+pub struct StructureView {
+    chapter_input: String,
+    section_input: String,
+    shown: StructureConfig, // 編集欄に反映済みのパターン
+    error: Option<String>,
+    unit: StructureUnit,
+    units: Vec<TextUnit>,
+    frequencies: Vec<HashMap<String, usize>>,
+    rows: Vec<(String, usize)>, // 単語と合計頻度 (降順)
+}
+
+impl Default for StructureView {
+    fn default() -> Self {
+        let config = StructureConfig::default();
+        Self {
+            chapter_input: config.chapter_patterns.join("\n"),
+            section_input: config.section_patterns.join("\n"),
+            shown: config,
+            error: None,
+            unit: StructureUnit::default(),
+            units: Vec::new(),
+            frequencies: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl StructureView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        // 他の操作 (プロジェクトの読み込みなど) で変わったパターンを編集欄に反映する
+        if self.shown != core.structure_config {
+            self.chapter_input = core.structure_config.chapter_patterns.join("\n");
+            self.section_input = core.structure_config.section_patterns.join("\n");
+            self.shown = core.structure_config.clone();
+            self.units.clear();
+            self.rows.clear();
+        }
+
+        // 見出しの検出パターン
+        ui.label("見出しの検出パターン (正規表現、1 行に 1 つ):");
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("章:");
+                ui.add(egui::TextEdit::multiline(&mut self.chapter_input).desired_rows(3));
+            });
+            ui.vertical(|ui| {
+                ui.label("節:");
+                ui.add(egui::TextEdit::multiline(&mut self.section_input).desired_rows(3));
+            });
+        });
+        if ui.button("パターンを適用").clicked() {
+            let patterns = |input: &str| -> Vec<String> {
+                input
+                    .lines()
+                    .map(|l| l.trim())
+                    .filter(|l| !l.is_empty())
+                    .map(|l| l.to_string())
+                    .collect()
+            };
+            let config = StructureConfig {
+                chapter_patterns: patterns(&self.chapter_input),
+                section_patterns: patterns(&self.section_input),
+            };
+            self.error = core
+                .set_structure_config(config)
+                .err()
+                .map(|e| e.to_string());
+            self.shown = core.structure_config.clone();
+            self.units.clear();
+            self.rows.clear();
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("正規表現のエラー: {}", error));
+        }
+
+        ui.label(format!(
+            "見出し: {}件 / 段落: {}件",
+            core.structure.headings.len(),
+            core.structure.paragraphs.len()
+        ));
+        if !core.structure.headings.is_empty() {
+            egui::CollapsingHeader::new("見出し一覧")
+                .id_source("structure_headings")
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source("structure_headings_scroll")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("structure_headings_grid")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.heading("行");
+                                    ui.heading("階層");
+                                    ui.heading("見出し");
+                                    ui.end_row();
+
+                                    for heading in &core.structure.headings {
+                                        ui.label(heading.line.to_string());
+                                        ui.label(heading.level.label());
+                                        ui.label(&heading.title);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                });
+        }
+
+        ui.separator();
+
+        // 単位ごとの頻度比較
+        ui.horizontal(|ui| {
+            ui.label("単位:");
+            egui::ComboBox::from_id_source("structure_unit")
+                .selected_text(self.unit.label())
+                .show_ui(ui, |ui| {
+                    for unit in StructureUnit::ALL {
+                        ui.selectable_value(&mut self.unit, unit, unit.label());
+                    }
+                });
+            if ui.button("単位ごとの頻度を比較").clicked() {
+                self.units = core.structure.units(self.unit);
+                self.frequencies = core.unit_frequencies(&self.units);
+                let mut totals: HashMap<&str, usize> = HashMap::new();
+                for counts in &self.frequencies {
+                    for (word, count) in counts {
+                        *totals.entry(word.as_str()).or_insert(0) += count;
+                    }
+                }
+                self.rows = totals
+                    .into_iter()
+                    .map(|(word, total)| (word.to_string(), total))
+                    .collect();
+                self.rows
+                    .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            }
+            if !self.units.is_empty() && ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("unit_frequencies.csv")
+                    .save_file()
+                {
+                    if let Err(e) =
+                        save_unit_frequencies_to_csv(&self.units, &self.frequencies, path)
+                    {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+
+        if self.units.is_empty() {
+            return;
+        }
+
+        ui.label(format!(
+            "{}の数: {} / 異なり語数: {}",
+            self.unit.label(),
+            self.units.len(),
+            self.rows.len()
+        ));
+        let mut columns = vec![
+            TableColumn::new("単語", 160.0),
+            TableColumn::new("合計", 60.0),
+        ];
+        columns.extend(
+            self.units
+                .iter()
+                .map(|u| TableColumn::new(u.label.as_str(), 100.0)),
+        );
+        egui::ScrollArea::horizontal()
+            .id_source("structure_compare_hscroll")
+            .show(ui, |ui| {
+                virtual_table(
+                    ui,
+                    "structure_compare_table",
+                    &columns,
+                    self.rows.len(),
+                    300.0,
+                    |row, index| {
+                        let (word, total) = &self.rows[index];
+                        row.cell(word.as_str());
+                        row.cell(total.to_string());
+                        for counts in &self.frequencies {
+                            row.cell(counts.get(word).copied().unwrap_or(0).to_string());
+                        }
+                    },
+                );
+            });
+    }
+}
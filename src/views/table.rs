@@ -0,0 +1,118 @@
+//! 見出しを固定し、表示範囲の行だけを描画する表
+
+use eframe::egui;
+
+/// 表の列
+pub struct TableColumn {
+    pub title: String,
+    pub width: f32, // 列幅 (0 以下なら残りの幅)
+}
+
+impl TableColumn {
+    pub fn new(title: impl Into<String>, width: f32) -> Self {
+        Self {
+            title: title.into(),
+            width,
+        }
+    }
+}
+
+/// 表の 1 行 (左の列から順にセルを追加する)
+pub struct TableRow<'a> {
+    ui: &'a mut egui::Ui,
+    columns: &'a [TableColumn],
+    height: f32,
+    next: usize,
+}
+
+impl TableRow<'_> {
+    /// 文字列のセル (列幅からはみ出した部分は表示しない)
+    pub fn cell(&mut self, text: impl Into<egui::WidgetText>) -> egui::Response {
+        self.add_cell(egui::Label::new(text).wrap(false))
+    }
+
+    /// クリックできる文字列のセル
+    pub fn clickable_cell(&mut self, text: impl Into<egui::WidgetText>) -> egui::Response {
+        self.add_cell(
+            egui::Label::new(text)
+                .wrap(false)
+                .sense(egui::Sense::click()),
+        )
+    }
+
+    fn add_cell(&mut self, label: egui::Label) -> egui::Response {
+        let width = match self.columns.get(self.next) {
+            Some(column) if column.width > 0.0 => column.width,
+            _ => self.ui.available_width(),
+        };
+        self.next += 1;
+        let (rect, _) = self
+            .ui
+            .allocate_exact_size(egui::vec2(width, self.height), egui::Sense::hover());
+        let mut cell = self
+            .ui
+            .child_ui(rect, egui::Layout::left_to_right(egui::Align::Center));
+        cell.set_clip_rect(rect.intersect(self.ui.clip_rect()));
+        cell.add(label)
+    }
+}
+
+/// 見出しを固定した表を表示する (スクロール位置に応じて見えている行だけ `add_row` を呼ぶ)
+///
+/// 見出しがクリックされた列の添字を返す。
+pub fn virtual_table(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    columns: &[TableColumn],
+    num_rows: usize,
+    max_height: f32,
+    mut add_row: impl FnMut(&mut TableRow<'_>, usize),
+) -> Option<usize> {
+    let height = ui.text_style_height(&egui::TextStyle::Body);
+    let mut clicked = None;
+
+    // 見出し (スクロールしても動かない)
+    ui.horizontal(|ui| {
+        let mut header = TableRow {
+            ui,
+            columns,
+            height,
+            next: 0,
+        };
+        for (i, column) in columns.iter().enumerate() {
+            let title = egui::RichText::new(column.title.as_str()).strong();
+            if header.clickable_cell(title).clicked() {
+                clicked = Some(i);
+            }
+        }
+    });
+    ui.separator();
+
+    egui::ScrollArea::vertical()
+        .id_source(id_source)
+        .max_height(max_height)
+        .auto_shrink([false, true])
+        .show_rows(ui, height, num_rows, |ui, range| {
+            for index in range {
+                ui.horizontal(|ui| {
+                    // 縞模様の背景は行を描いた後に大きさを決める
+                    let background = ui.painter().add(egui::Shape::Noop);
+                    let mut row = TableRow {
+                        ui,
+                        columns,
+                        height,
+                        next: 0,
+                    };
+                    add_row(&mut row, index);
+                    if index % 2 == 1 {
+                        let rect = ui.min_rect();
+                        ui.painter().set(
+                            background,
+                            egui::Shape::rect_filled(rect, 0.0, ui.visuals().faint_bg_color),
+                        );
+                    }
+                });
+            }
+        });
+    clicked
+}
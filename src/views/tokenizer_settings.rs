@@ -0,0 +1,81 @@
+use eframe::egui;
+use jp_word_segment::{SegmentMode, TokenizerCore, TokenizerSettings};
+use rfd::FileDialog;
+
+/// 形態素解析器の設定 (分割モード・ユーザー辞書) を編集するビュー
+#[derive(Default)]
+pub struct TokenizerSettingsView {
+    settings: TokenizerSettings, // 編集中の設定
+    shown: TokenizerSettings,    // `settings` に反映済みの TokenizerCore の設定
+    error: Option<String>,
+}
+
+impl TokenizerSettingsView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        // 他の操作 (プロジェクトの読み込みなど) で変わった設定を編集欄に反映する
+        if self.shown != core.settings {
+            self.settings = core.settings.clone();
+            self.shown = core.settings.clone();
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("分割モード:");
+            egui::ComboBox::from_id_source("tokenizer_mode")
+                .selected_text(self.settings.mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in SegmentMode::ALL {
+                        ui.selectable_value(&mut self.settings.mode, mode, mode.label());
+                    }
+                });
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("ユーザー辞書:");
+            ui.label(self.settings.user_dictionary.as_deref().unwrap_or("なし"));
+            if ui.button("選択").clicked() {
+                if let Some(path) = FileDialog::new().add_filter("CSV", &["csv"]).pick_file() {
+                    self.settings.user_dictionary = Some(path.to_string_lossy().into_owned());
+                }
+            }
+            if self.settings.user_dictionary.is_some() && ui.button("解除").clicked() {
+                self.settings.user_dictionary = None;
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            let compound = &mut self.settings.compound;
+            ui.checkbox(&mut compound.enabled, "連続する名詞を複合名詞にまとめる");
+            ui.add_enabled_ui(compound.enabled, |ui| {
+                ui.checkbox(&mut compound.prefixes, "接頭詞を含める");
+                ui.checkbox(&mut compound.suffixes, "接尾を含める");
+                ui.checkbox(&mut compound.numbers, "数を含める");
+            });
+        });
+
+        let changed = self.settings != core.settings;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(changed, egui::Button::new("設定を適用して再解析"))
+                .clicked()
+            {
+                match core.set_tokenizer_settings(self.settings.clone()) {
+                    Ok(()) => {
+                        self.error = None;
+                        self.shown = core.settings.clone();
+                        if !core.tokens.is_empty() {
+                            core.analyze_text();
+                        }
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+            if changed {
+                ui.label("(未適用)");
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("形態素解析器を作成できません: {}", error),
+            );
+        }
+    }
+}
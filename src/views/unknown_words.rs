@@ -0,0 +1,248 @@
+use super::table::{virtual_table, TableColumn};
+use eframe::egui;
+use jp_word_segment::{
+    save_unknown_words_to_csv, save_user_words, CandidateConfig, CandidateKind,
+    DictionaryCandidate, TokenizerCore, UnknownWord, UserWord,
+};
+use rfd::FileDialog;
+
+/// 未知語の文脈の前後の語数
+const CONTEXT_SIZE: usize = 5;
+/// 未知語ごとに残す文脈の数
+const MAX_CONTEXTS: usize = 20;
+
+/// 未知語の一覧と辞書登録候補のビュー
+#[derive(Default)]
+pub struct UnknownWordView {
+    config: CandidateConfig,
+    unknown: Vec<UnknownWord>,
+    candidates: Vec<DictionaryCandidate>,
+    checked: Vec<bool>,               // 登録・保存する候補
+    selected: Option<usize>,          // 文脈を表示する未知語 (`unknown` の添字)
+    revision: Option<u64>,            // 一覧を作った TokenizerCore の revision
+    applied: Option<CandidateConfig>, // `candidates` を作った条件
+    error: Option<String>,
+}
+
+impl UnknownWordView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        if core.tokens.is_empty() {
+            ui.label("「解析」ボタンを押すと辞書にない語が表示されます。");
+            return;
+        }
+        if self.revision != Some(core.revision) {
+            self.unknown = core.unknown_words(CONTEXT_SIZE, MAX_CONTEXTS);
+            self.selected = None;
+            self.applied = None;
+            self.revision = Some(core.revision);
+        }
+
+        self.unknown_ui(ui);
+        ui.separator();
+        self.candidates_ui(ui, core);
+    }
+
+    /// 未知語の一覧
+    fn unknown_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "未知語: {}語 (行をクリックすると出現箇所を表示)",
+                self.unknown.len()
+            ));
+            if !self.unknown.is_empty() && ui.button("CSVファイルに保存").clicked() {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("unknown_words.csv")
+                    .save_file()
+                {
+                    if let Err(e) = save_unknown_words_to_csv(&self.unknown, path) {
+                        eprintln!("CSV保存エラー: {}", e);
+                    }
+                }
+            }
+        });
+
+        let columns = [
+            TableColumn::new("単語", 160.0),
+            TableColumn::new("推定品詞", 180.0),
+            TableColumn::new("頻度", 60.0),
+            TableColumn::new("文脈", 0.0),
+        ];
+        let mut clicked = None;
+        virtual_table(
+            ui,
+            "unknown_words_table",
+            &columns,
+            self.unknown.len(),
+            200.0,
+            |row, index| {
+                let word = &self.unknown[index];
+                if row.clickable_cell(word.word.as_str()).clicked() {
+                    clicked = Some(index);
+                }
+                row.cell(word.pos.as_str());
+                row.cell(word.frequency.to_string());
+                if let Some(context) = word.contexts.first() {
+                    row.cell(format!(
+                        "{}【{}】{}",
+                        context.left_context, context.keyword, context.right_context
+                    ));
+                }
+            },
+        );
+        if clicked.is_some() {
+            self.selected = clicked;
+        }
+
+        if let Some(word) = self.selected.and_then(|i| self.unknown.get(i)) {
+            ui.label(format!(
+                "「{}」の出現箇所 ({}件中 {}件):",
+                word.word,
+                word.frequency,
+                word.contexts.len()
+            ));
+            egui::ScrollArea::vertical()
+                .id_source("unknown_word_contexts")
+                .max_height(120.0)
+                .show(ui, |ui| {
+                    for context in &word.contexts {
+                        ui.label(format!(
+                            "{}行目: {}【{}】{}",
+                            context.line_number,
+                            context.left_context,
+                            context.keyword,
+                            context.right_context
+                        ));
+                    }
+                });
+        }
+    }
+
+    /// 辞書登録候補の一覧
+    fn candidates_ui(&mut self, ui: &mut egui::Ui, core: &mut TokenizerCore) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("辞書登録候補:");
+            ui.checkbox(&mut self.config.katakana, "カタカナ連続");
+            ui.checkbox(&mut self.config.nouns, "名詞連続");
+            ui.label("最小頻度:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_frequency)
+                    .speed(1.0)
+                    .clamp_range(1..=1000),
+            );
+            ui.label("最大語数:");
+            ui.add(
+                egui::DragValue::new(&mut self.config.max_parts)
+                    .speed(0.1)
+                    .clamp_range(2..=10),
+            );
+        });
+        if self.applied.as_ref() != Some(&self.config) {
+            self.candidates = core.dictionary_candidates(&self.config);
+            self.checked = vec![false; self.candidates.len()];
+            self.applied = Some(self.config.clone());
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(format!(
+                "候補: {}語 / 選択: {}語",
+                self.candidates.len(),
+                self.checked.iter().filter(|&&c| c).count()
+            ));
+            for kind in CandidateKind::ALL {
+                if ui
+                    .small_button(format!("{}をすべて選択", kind.label()))
+                    .clicked()
+                {
+                    for (checked, candidate) in self.checked.iter_mut().zip(&self.candidates) {
+                        *checked |= candidate.kind == kind;
+                    }
+                }
+            }
+            if ui.small_button("選択を解除").clicked() {
+                self.checked.fill(false);
+            }
+        });
+
+        let columns = [
+            TableColumn::new("", 24.0),
+            TableColumn::new("候補", 180.0),
+            TableColumn::new("種類", 100.0),
+            TableColumn::new("品詞", 140.0),
+            TableColumn::new("読み", 180.0),
+            TableColumn::new("頻度", 60.0),
+            TableColumn::new("現在の分割", 0.0),
+        ];
+        let mut toggled = None;
+        virtual_table(
+            ui,
+            "dictionary_candidates_table",
+            &columns,
+            self.candidates.len(),
+            250.0,
+            |row, index| {
+                let candidate = &self.candidates[index];
+                let mark = if self.checked[index] { "☑" } else { "☐" };
+                if row.clickable_cell(mark).clicked() {
+                    toggled = Some(index);
+                }
+                if row.clickable_cell(candidate.surface.as_str()).clicked() {
+                    toggled = Some(index);
+                }
+                row.cell(candidate.kind.label());
+                row.cell(candidate.pos.as_str());
+                row.cell(candidate.reading.as_str());
+                row.cell(candidate.frequency.to_string());
+                row.cell(candidate.parts.join(" / "));
+            },
+        );
+        if let Some(index) = toggled {
+            self.checked[index] = !self.checked[index];
+        }
+
+        let words: Vec<UserWord> = self
+            .candidates
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, &checked)| checked)
+            .map(|(candidate, _)| candidate.to_user_word())
+            .collect();
+        ui.add_enabled_ui(!words.is_empty(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("ユーザー辞書形式で保存").clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("user_dictionary.csv")
+                        .save_file()
+                    {
+                        if let Err(e) = save_user_words(&words, path) {
+                            self.error = Some(format!("保存できませんでした: {}", e));
+                        }
+                    }
+                }
+                if ui.button("ユーザー辞書に登録して再解析").clicked() {
+                    let path = core
+                        .settings
+                        .user_dictionary
+                        .clone()
+                        .map(std::path::PathBuf::from)
+                        .or_else(|| {
+                            FileDialog::new()
+                                .add_filter("CSV", &["csv"])
+                                .set_file_name("user_dictionary.csv")
+                                .save_file()
+                        });
+                    if let Some(path) = path {
+                        self.error = core
+                            .register_user_words(path, &words)
+                            .err()
+                            .map(|e| format!("登録できませんでした: {}", e));
+                    }
+                }
+            });
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}